@@ -32,7 +32,10 @@ fn main() -> io::Result<()> {
     let path = read_to_string(file)?;
 
     let tokens = TokenStream::new(&path);
-    let scope = Scope::from_tokens(&path, tokens);
+    let (scope, diagnostics) = Scope::parse(&path, tokens);
+    for diagnostic in &diagnostics {
+        diagnostic.eprint(&path);
+    }
 
     println!("{scope}");
 
@@ -5,9 +5,10 @@ use tracing::{debug, info, trace};
 use wollok_lexer::{
     lexer::TokenStream,
     macros::{T, cmt},
+    token::{Span, SpannedToken, Token},
 };
 
-use crate::{expr::Expr, item::Item, source::Ast};
+use crate::{error::Diagnostic, expr::Expr, item::Item, source::Ast, span::Spanned};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Scope(pub Vec<Stmt>);
@@ -32,8 +33,28 @@ impl std::ops::Deref for Scope {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
-    Item(Item),
-    Expr(Expr),
+    Item(Spanned<Item>),
+    Expr(Spanned<Expr>),
+}
+
+impl Stmt {
+    /// Location of the whole statement in the source code
+    #[must_use]
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Item(item) => item.span,
+            Stmt::Expr(expr) => expr.span,
+        }
+    }
+
+    /// Whether running this statement always ends in a `return` (or a `throw`)
+    #[must_use]
+    pub fn always_returns(&self) -> bool {
+        match self {
+            Stmt::Item(_) => false,
+            Stmt::Expr(expr) => expr.always_returns(),
+        }
+    }
 }
 
 impl Display for Stmt {
@@ -46,15 +67,31 @@ impl Display for Stmt {
 }
 
 impl Scope {
+    /// Parses the whole input, discarding the diagnostics (see [`Scope::parse`]).
+    /// Erroneous statements are kept as `Expr::Error`/`Item::Error` placeholders.
     #[must_use]
+    #[deprecated(note = "use `Scope::parse`, which also returns the diagnostics")]
     pub fn from_tokens(base: &str, tokens: TokenStream<'_>) -> Self {
+        Self::parse(base, tokens).0
+    }
+
+    /// Parses the whole input recovering from lexer and syntax errors,
+    /// returning the resulting scope along with all the diagnostics found,
+    /// including the ones of [`Scope::check_initializers`]
+    #[must_use]
+    pub fn parse(base: &str, tokens: TokenStream<'_>) -> (Self, Vec<Diagnostic>) {
         info!("Starting AST parsing from tokens for base: {}", base);
-        let result = Ast::new(base, tokens).parse_scope();
+        let mut ast = Ast::new(base, tokens);
+        let result = ast.parse_scope();
         info!(
-            "Successfully parsed AST scope with {} statements",
-            result.0.len()
+            "Parsed AST scope with {} statements and {} diagnostics",
+            result.0.len(),
+            ast.diagnostics.len()
         );
-        result
+        ast.diagnostics.extend(result.check_initializers());
+        // Lexer, parser and initializer diagnostics are reported in source order
+        ast.diagnostics.sort_by_key(|d| d.span.from);
+        (result, ast.diagnostics)
     }
 }
 
@@ -71,7 +108,7 @@ impl Ast<'_> {
             }
 
             trace!("Parsing statement in scope");
-            let stmt = self.parse_statement();
+            let stmt = self.parse_statement_recovering();
             Self::push_to_node(stmt, &mut nodes);
         }
 
@@ -96,6 +133,10 @@ impl Ast<'_> {
                     _ = first.accept();
                     trace!("Skipped comment token in pre-statement");
                 }
+                wollok_lexer::token::Token::DocComment(_) => {
+                    let doc = first.accept();
+                    self.take_doc_comment(doc);
+                }
                 _ => {
                     first.recover();
                     return true;
@@ -104,17 +145,31 @@ impl Ast<'_> {
         }
     }
 
-    /// Skips comment tokens in the token stream
+    /// Skips comment tokens in the token stream.
+    /// Comments are trivia, so they don't extend the span of the previous node.
     pub(crate) fn skip_comments(&mut self) {
+        let last_offset = self.last_offset;
         while let Some(token) = self.peek() {
             if matches!(**token, cmt!(@match _)) {
                 trace!("skipping comment");
                 _ = token.accept();
+            } else if matches!(**token, Token::DocComment(_)) {
+                let doc = token.accept();
+                self.take_doc_comment(doc);
             } else {
                 token.recover();
                 break;
             }
         }
+        self.last_offset = last_offset;
+    }
+
+    /// Keeps a skipped doc comment for the next item, replacing any previous one
+    fn take_doc_comment(&mut self, token: SpannedToken) {
+        if let Token::DocComment(doc) = token.token {
+            trace!("Keeping doc comment for the next item");
+            self.pending_doc = Some(doc);
+        }
     }
 
     pub(crate) fn push_to_node<T>(stmt: T, nodes: &mut Vec<T>) {
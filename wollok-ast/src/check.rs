@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+
+use crate::{
+    ast::{Scope, Stmt},
+    error::Diagnostic,
+    expr::{Block, Expr, ExprClass},
+    item::{Item, ItemClass, ItemLet, ItemMethod, ItemMixin, ItemProperty},
+    span::Spanned,
+};
+
+impl Scope {
+    /// Matches the named initializers of every `new` against the attributes of
+    /// the class, including the ones it inherits from its superclasses and mixins,
+    /// reporting the unknown and repeated ones.
+    ///
+    /// Classes that are not declared in this scope cannot be checked, so only
+    /// repeated initializers are reported for them.
+    #[must_use]
+    pub fn check_initializers(&self) -> Vec<Diagnostic> {
+        let mut check = InitializerCheck::default();
+        for stmt in &self.0 {
+            if let Stmt::Item(item) = stmt {
+                check.declare(item);
+            }
+        }
+        for stmt in &self.0 {
+            check.stmt(stmt);
+        }
+        debug!(
+            "Checked initializers with {} diagnostics",
+            check.diagnostics.len()
+        );
+        check.diagnostics
+    }
+}
+
+#[derive(Default)]
+struct InitializerCheck<'a> {
+    classes: HashMap<&'a str, &'a ItemClass>,
+    mixins: HashMap<&'a str, &'a ItemMixin>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> InitializerCheck<'a> {
+    /// Registers the classes and mixins declared in `item`, also inside packages and describes
+    fn declare(&mut self, item: &'a Item) {
+        match item {
+            Item::Class(class) => {
+                self.classes.insert(class.name.as_str(), class);
+            }
+            Item::Mixin(mixin) => {
+                self.mixins.insert(mixin.name.as_str(), mixin);
+            }
+            Item::Package(package) => package.body.iter().for_each(|item| self.declare(item)),
+            Item::Describe(describe) => describe.body.iter().for_each(|item| self.declare(item)),
+            _ => {}
+        }
+    }
+
+    /// Every attribute `class` has, or `None` when part of its hierarchy is not declared here
+    fn attributes(&self, class: &'a ItemClass) -> Option<HashSet<&'a str>> {
+        let mut attributes = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![class];
+        while let Some(class) = pending.pop() {
+            if !visited.insert(class.name.as_str()) {
+                continue;
+            }
+            attributes.extend(class.attributes());
+            for mixin in &class.mixins {
+                attributes.extend(self.mixins.get(mixin.as_str())?.attributes());
+            }
+            for superclass in class.superclass.iter().flatten() {
+                pending.push(self.classes.get(superclass.as_str())?);
+            }
+        }
+        Some(attributes)
+    }
+
+    fn instantiation(&mut self, new: &ExprClass) {
+        let attributes = self
+            .classes
+            .get(new.name.as_str())
+            .and_then(|class| self.attributes(class));
+        for (i, arg) in new.named.iter().enumerate() {
+            if attributes
+                .as_ref()
+                .is_some_and(|attributes| !attributes.contains(arg.name.as_str()))
+            {
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        arg.span,
+                        format!("Class `{}` has no attribute `{}`", new.name, arg.name),
+                    )
+                    .with_label(arg.name.span, "unknown attribute"),
+                );
+            } else if new.named[..i].iter().any(|other| other.name == arg.name) {
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        arg.span,
+                        format!("Attribute `{}` is initialized twice", arg.name),
+                    )
+                    .with_label(arg.name.span, "repeated initializer"),
+                );
+            }
+            self.expr(&arg.value);
+        }
+        self.exprs(&new.params);
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Item(item) => self.item(item),
+            Stmt::Expr(expr) => self.expr(expr),
+        }
+    }
+
+    fn block(&mut self, block: &Block) {
+        for stmt in &block.stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn items(&mut self, items: &[Spanned<Item>]) {
+        for item in items {
+            self.item(item);
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        match item {
+            Item::Const(item) => self.expr(&item.expr),
+            Item::Let(ItemLet {
+                expr: Some(expr), ..
+            })
+            | Item::Property(ItemProperty {
+                expr: Some(expr), ..
+            }) => self.expr(expr),
+            Item::Method(method) => self.method(method),
+            Item::PrefixedMethod(prefixed) => self.method(&prefixed.method),
+            Item::Class(class) => self.items(&class.body),
+            Item::Object(object) => {
+                if let Some(superclass) = &object.superclass {
+                    self.instantiation(superclass);
+                }
+                self.items(&object.body);
+            }
+            Item::Mixin(mixin) => self.items(&mixin.body),
+            Item::Test(test) => self.block(&test.body),
+            Item::Describe(describe) => self.items(&describe.body),
+            Item::Fixture(fixture) => self.block(&fixture.body),
+            Item::Program(program) => self.block(&program.body),
+            Item::Package(package) => self.items(&package.body),
+            Item::Let(_) | Item::Property(_) | Item::Import(_) | Item::Error => {}
+        }
+    }
+
+    fn method(&mut self, method: &ItemMethod) {
+        if let Some(body) = method.body.block() {
+            self.block(body);
+        }
+    }
+
+    fn exprs(&mut self, exprs: &[Spanned<Expr>]) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Array(expr) => self.exprs(&expr.elements),
+            Expr::Set(expr) => self.exprs(&expr.elements),
+            Expr::Tuple(expr) => self.exprs(&expr.elements),
+            Expr::Assign(expr) => {
+                self.expr(&expr.left);
+                self.expr(&expr.right);
+            }
+            Expr::Block(expr) => self.block(&expr.block),
+            Expr::Call(expr) => {
+                self.expr(&expr.callee);
+                self.exprs(&expr.args);
+            }
+            Expr::Closure(expr) => self.block(&expr.body),
+            Expr::Const(expr) => self.expr(&expr.block),
+            Expr::Field(expr) => self.expr(&expr.base),
+            Expr::Class(expr) => self.instantiation(expr),
+            Expr::If(expr) => {
+                self.expr(&expr.condition);
+                self.block(&expr.then);
+                if let Some(otherwise) = &expr.otherwise {
+                    self.expr(otherwise);
+                }
+            }
+            Expr::Let(expr) => self.expr(&expr.value),
+            Expr::MethodCall(expr) => {
+                self.expr(&expr.receiver);
+                self.exprs(&expr.args);
+            }
+            Expr::Object(object) => {
+                if let Some(superclass) = &object.superclass {
+                    self.instantiation(superclass);
+                }
+                self.items(&object.body);
+            }
+            Expr::Paren(expr) => self.expr(&expr.expr),
+            Expr::Return(expr) => {
+                if let Some(value) = &expr.value {
+                    self.expr(value);
+                }
+            }
+            Expr::Throw(expr) => self.expr(&expr.expr),
+            Expr::Try(expr) => self.expr(&expr.expr),
+            Expr::TryBlock(expr) => {
+                self.block(&expr.block);
+                expr.catches
+                    .iter()
+                    .for_each(|catch| self.block(&catch.body));
+                if let Some(always) = &expr.always {
+                    self.block(always);
+                }
+            }
+            Expr::Unary(expr) => self.expr(&expr.expr),
+            Expr::Propagate(expr) => self.expr(&expr.expr),
+            Expr::Assert(expr) => self.expr(&expr.expr),
+            Expr::Super(expr) => self.exprs(&expr.args),
+            Expr::New(expr) => self.exprs(&expr.args),
+            Expr::Lit(_) | Expr::Self_ | Expr::Error => {}
+        }
+    }
+}
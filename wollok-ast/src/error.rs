@@ -0,0 +1,59 @@
+use ariadne::{Color, Label, Report, ReportKind, Source};
+use std::fmt;
+use wollok_lexer::{error::LexerErr, token::Span};
+
+pub type Result<T> = std::result::Result<T, Diagnostic>;
+
+/// A syntax error with its location in the source code
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+    pub labels: Vec<(Span, String)>,
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn new(span: Span, message: impl fmt::Display) -> Self {
+        Self {
+            span,
+            message: message.to_string(),
+            labels: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_label(mut self, span: Span, msg: impl fmt::Display) -> Self {
+        self.labels.push((span, msg.to_string()));
+        self
+    }
+
+    /// Prints the diagnostic to stderr as an `ariadne` report over `base`
+    pub fn eprint(&self, base: &str) {
+        _ = Report::build(ReportKind::Error, self.span)
+            .with_message(&self.message)
+            .with_labels(self.labels.iter().map(|(span, msg)| {
+                Label::new(*span)
+                    .with_message(msg)
+                    .with_color(Color::BrightRed)
+            }))
+            .finish()
+            .eprint(Source::from(base));
+    }
+}
+
+impl From<LexerErr<'_>> for Diagnostic {
+    fn from(err: LexerErr<'_>) -> Self {
+        Self {
+            span: err.span,
+            message: err.message().unwrap_or("Invalid token").to_owned(),
+            labels: err.labels,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
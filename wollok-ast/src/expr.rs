@@ -1,24 +1,57 @@
 use owo_colors::OwoColorize;
 use std::fmt::Display;
 
-use wollok_common::ast::{BinaryOp, UnaryOp};
+use wollok_common::ast::UnaryOp;
 use wollok_lexer::token::Literal;
 
-use crate::ast::Stmt;
+use crate::{
+    ast::Stmt,
+    item::{Ident, Item, write_object_tail},
+    span::Spanned,
+};
 
+/// A sequence of statements. The span of a block includes its braces;
+/// inline bodies and single expression branches have none, so they span
+/// just the expression.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Block {
     pub stmts: Vec<Stmt>,
 }
 
+impl Block {
+    /// Whether every path through the block ends in a `return` (or a `throw`)
+    #[must_use]
+    pub fn always_returns(&self) -> bool {
+        self.stmts.iter().any(Stmt::always_returns)
+    }
+
+    /// Whether any path through the block has a `return`.
+    /// Returns inside closures belong to the closure, so they are not counted.
+    #[must_use]
+    pub fn has_return(&self) -> bool {
+        self.stmts.iter().any(|stmt| match stmt {
+            Stmt::Expr(expr) => expr.has_return(),
+            Stmt::Item(_) => false,
+        })
+    }
+
+    /// Statements that can never run because a previous one always returns
+    #[must_use]
+    pub fn unreachable(&self) -> &[Stmt] {
+        self.stmts
+            .iter()
+            .position(Stmt::always_returns)
+            .map_or(&[], |idx| &self.stmts[idx + 1..])
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
 pub enum Expr {
     Array(ExprArray),
     Set(ExprSet),
     Assign(ExprAssign),
-    Binary(ExprBinary),
-    // Block(ExprBlock),
+    Block(ExprBlock),
     Call(ExprCall),
     Closure(ExprClosure),
     Const(ExprConst),
@@ -37,79 +70,127 @@ pub enum Expr {
     Paren(ExprParen),
     // Path(ExprPath),
     Return(ExprReturn),
+    Throw(ExprThrow),
     Try(ExprTry),
     TryBlock(ExprTryBlock),
     Tuple(ExprTuple),
     Unary(ExprUnary),
+    Propagate(ExprPropagate),
+    Assert(ExprAssert),
     // While(ExprWhile),
     Self_,
     Super(ExprSuper),
     New(ExprNew),
+    /// Placeholder for an expression that failed to parse
+    Error,
+}
+
+impl Expr {
+    /// Whether evaluating this expression always ends in a `return` (or a `throw`)
+    #[must_use]
+    pub fn always_returns(&self) -> bool {
+        match self {
+            Expr::Return(_) | Expr::Throw(_) => true,
+            Expr::Block(expr) => expr.block.always_returns(),
+            Expr::If(expr) => {
+                expr.then.always_returns()
+                    && expr
+                        .otherwise
+                        .as_ref()
+                        .is_some_and(|otherwise| otherwise.always_returns())
+            }
+            Expr::TryBlock(expr) => expr.always_returns(),
+            _ => false,
+        }
+    }
+
+    /// Whether this expression has a `return` in any of its branches
+    #[must_use]
+    pub fn has_return(&self) -> bool {
+        match self {
+            Expr::Return(_) => true,
+            Expr::Block(expr) => expr.block.has_return(),
+            Expr::If(expr) => {
+                expr.then.has_return()
+                    || expr
+                        .otherwise
+                        .as_ref()
+                        .is_some_and(|otherwise| otherwise.has_return())
+            }
+            Expr::TryBlock(expr) => {
+                expr.block.has_return()
+                    || expr.catches.iter().any(|catch| catch.body.has_return())
+                    || expr
+                        .always
+                        .as_ref()
+                        .is_some_and(|always| always.has_return())
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprArray {
-    pub elements: Vec<Expr>,
+    pub elements: Vec<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprSet {
-    pub elements: Vec<Expr>,
+    pub elements: Vec<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprAssign {
-    pub left: Box<Expr>,
-    pub right: Box<Expr>,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub struct ExprBinary {
-    pub left: Box<Expr>,
-    pub right: Box<Expr>,
-    pub op: BinaryOp,
+    pub left: Box<Spanned<Expr>>,
+    pub right: Box<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprCall {
-    pub callee: Box<Expr>,
-    pub args: Vec<Expr>,
+    pub callee: Box<Spanned<Expr>>,
+    pub args: Vec<Spanned<Expr>>,
 }
 
 // A lambda expression / closure en Wollok: { param1, param2 => body }
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprClosure {
-    pub params: Vec<String>, // nombres de parámetros
-    pub body: Box<Expr>,
+    pub params: Vec<Spanned<Ident>>,
+    pub body: Spanned<Block>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprConst {
-    pub block: Box<Expr>,
+    pub block: Box<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprField {
-    pub base: Box<Expr>,
-    pub name: String,
+    pub base: Box<Spanned<Expr>>,
+    pub name: Spanned<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprBlock {
+    pub block: Block,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprIf {
-    pub condition: Box<Expr>,
-    pub then: Block,
-    pub otherwise: Option<Box<Expr>>,
+    pub condition: Box<Spanned<Expr>>,
+    pub then: Spanned<Block>,
+    pub otherwise: Option<Box<Spanned<Expr>>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprReturn {
-    pub value: Option<Box<Expr>>,
+    pub value: Option<Box<Spanned<Expr>>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprLet {
     pub name: String,
-    pub value: Box<Expr>,
+    pub value: Box<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -119,59 +200,145 @@ pub struct ExprLit {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprMethodCall {
-    pub receiver: Box<Expr>, // objeto al que se le envía el mensaje
-    pub name: String,
-    pub args: Vec<Expr>,
+    pub receiver: Box<Spanned<Expr>>, // objeto al que se le envía el mensaje
+    pub name: Spanned<String>,
+    pub args: Vec<Spanned<Expr>>,
+}
+
+impl ExprMethodCall {
+    /// The message send a binary operator stands for, `a + b` is `a.+(b)`.
+    /// The right side of `&&` and `||` (also written `and` and `or`) is only
+    /// evaluated when needed, so it is sent inside a closure: `a.&&({ b })`
+    #[must_use]
+    pub fn binary(left: Spanned<Expr>, selector: Spanned<String>, right: Spanned<Expr>) -> Self {
+        let right = if is_lazy_operator(&selector) {
+            let span = right.span;
+            let body = Block {
+                stmts: vec![Stmt::Expr(right)],
+            };
+            let closure = Expr::Closure(ExprClosure {
+                params: Vec::new(),
+                body: Spanned::new(span, body),
+            });
+            Spanned::new(span, closure)
+        } else {
+            right
+        };
+        Self {
+            receiver: Box::new(left),
+            name: selector,
+            args: vec![right],
+        }
+    }
+}
+
+/// Operators whose right side is sent as a closure, see [`ExprMethodCall::binary`]
+fn is_lazy_operator(selector: &str) -> bool {
+    matches!(selector, "&&" | "||" | "and" | "or")
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprParen {
-    pub expr: Box<Expr>,
+    pub expr: Box<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprTry {
-    pub expr: Box<Expr>,
+    pub expr: Box<Spanned<Expr>>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprThrow {
+    pub expr: Box<Spanned<Expr>>,
+}
+
+/// `try { ... } catch e : Type { ... } then always { ... }`
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprTryBlock {
-    pub block: Block,
+    pub block: Spanned<Block>,
+    pub catches: Vec<Spanned<CatchClause>>,
+    pub always: Option<Spanned<Block>>,
+}
+
+impl ExprTryBlock {
+    /// A `then always` block that returns overrides everything else.
+    /// Otherwise the body and every handler must return, since exceptions
+    /// that no handler catches are rethrown.
+    #[must_use]
+    pub fn always_returns(&self) -> bool {
+        self.always
+            .as_ref()
+            .is_some_and(|always| always.always_returns())
+            || (self.block.always_returns()
+                && self.catches.iter().all(|catch| catch.body.always_returns()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatchClause {
+    pub param: Spanned<Ident>,
+    /// Exception class being caught, every exception when missing
+    pub kind: Option<String>,
+    pub body: Spanned<Block>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprTuple {
-    pub elements: Vec<Expr>,
+    pub elements: Vec<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprUnary {
     pub op: UnaryOp,
-    pub expr: Box<Expr>,
+    pub expr: Box<Spanned<Expr>>,
+}
+
+/// `expr?`: returns the error to the caller if the fallible call failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprPropagate {
+    pub expr: Box<Spanned<Expr>>,
+}
+
+/// `expr!`: panics at runtime if the fallible call failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprAssert {
+    pub expr: Box<Spanned<Expr>>,
 }
 
 // Expresiones específicas de Wollok
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprSuper {
-    pub args: Vec<Expr>,
+    pub args: Vec<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprNew {
     pub class_name: String,
-    pub args: Vec<Expr>,
+    pub args: Vec<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprObject {
-    pub fields: Vec<Expr>,
+    pub superclass: Option<Spanned<ExprClass>>,
+    pub mixins: Vec<Spanned<String>>,
+    pub body: Vec<Spanned<Item>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExprClass {
     pub name: String,
-    pub params: Vec<Expr>,
+    /// Positional arguments, as in `new Libro(1, 2)`
+    pub params: Vec<Spanned<Expr>>,
+    /// Named initializers, as in `new Ave(energia = 10)`
+    pub named: Vec<Spanned<NamedArg>>,
+}
+
+/// An attribute initializer inside `new`: `energia = 10`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedArg {
+    pub name: Spanned<String>,
+    pub value: Spanned<Expr>,
 }
 
 impl Display for Expr {
@@ -180,7 +347,6 @@ impl Display for Expr {
             Expr::Array(expr) => expr,
             Expr::Set(expr) => expr,
             Expr::Assign(expr) => expr,
-            Expr::Binary(expr) => expr,
             Expr::Call(expr) => expr,
             Expr::Closure(expr) => expr,
             Expr::Const(expr) => expr,
@@ -193,13 +359,18 @@ impl Display for Expr {
             Expr::Object(expr) => expr,
             Expr::Paren(expr) => expr,
             Expr::Return(expr) => expr,
+            Expr::Throw(expr) => expr,
             Expr::Try(expr) => expr,
+            Expr::Block(expr) => expr,
             Expr::TryBlock(expr) => expr,
             Expr::Tuple(expr) => expr,
             Expr::Unary(expr) => expr,
+            Expr::Propagate(expr) => expr,
+            Expr::Assert(expr) => expr,
             Expr::Self_ => &"Self",
             Expr::Super(expr) => expr,
             Expr::New(expr) => expr,
+            Expr::Error => &"<error>",
         };
         write!(f, "{v}")
     }
@@ -237,17 +408,6 @@ impl Display for ExprAssign {
     }
 }
 
-impl Display for ExprBinary {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let lhs = self.left.as_ref();
-        let op = &self.op;
-        let rhs = self.right.as_ref();
-        _ = write!(f, "{lhs}");
-        _ = write!(f, " {op} ");
-        write!(f, "{rhs}")
-    }
-}
-
 impl Display for ExprCall {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let call = self.callee.as_ref().white().to_string();
@@ -268,14 +428,16 @@ impl Display for ExprClosure {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", "{ ".yellow())?;
         for (i, param) in self.params.iter().enumerate() {
-            write!(f, "{}", param.cyan())?;
+            write!(f, "{}", param.name.cyan())?;
             if i < self.params.len() - 1 {
                 write!(f, ", ")?;
             }
         }
-        write!(f, "{}", " => ".yellow())?;
-        write!(f, "{}", self.body)?;
-        write!(f, "{}", " }".yellow())
+        write!(f, "{}", " -> ".yellow())?;
+        for stmt in &self.body.stmts {
+            write!(f, "{stmt}; ")?;
+        }
+        write!(f, "{}", "}".yellow())
     }
 }
 
@@ -287,7 +449,7 @@ impl Display for ExprConst {
 
 impl Display for ExprField {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.base.as_ref() {
+        match self.base.node {
             Expr::Self_ => write!(f, "{}", self.name.blue()),
             _ => write!(f, "{}.{}", self.base, self.name.blue()),
         }
@@ -305,10 +467,28 @@ impl Display for ExprClass {
                 write!(f, ", ")?;
             }
         }
+        for (i, arg) in self.named.iter().enumerate() {
+            write!(f, "{arg}")?;
+            if i < self.named.len() - 1 {
+                write!(f, ", ")?;
+            }
+        }
         write!(f, ")")
     }
 }
 
+impl Display for NamedArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.name.cyan(), " = ".white(), self.value)
+    }
+}
+
+impl Display for ExprBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.block)
+    }
+}
+
 impl Display for ExprIf {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", "(expr) ".yellow())?;
@@ -339,7 +519,7 @@ impl Display for ExprLit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.value {
             Literal::String(s) => {
-                write!(f, "{}", format!("\"{s}\"").green())
+                write!(f, "{}", s.raw.green())
             }
             Literal::Integer(n) => write!(f, "{}", n.to_string().yellow()),
             Literal::Float(n) => write!(f, "{}", n.to_string().yellow()),
@@ -351,6 +531,21 @@ impl Display for ExprLit {
 
 impl Display for ExprMethodCall {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Operators are written back as `a + b`, and `a && b` without its closure
+        if let [arg] = self.args.as_slice() {
+            if is_lazy_operator(&self.name)
+                && let Expr::Closure(ExprClosure { params, body }) = &arg.node
+                && let ([], [stmt]) = (params.as_slice(), body.stmts.as_slice())
+            {
+                return write!(f, "{} {} {stmt}", self.receiver, self.name.bright_red());
+            }
+            if !self
+                .name
+                .starts_with(|c: char| c.is_alphabetic() || c == '_')
+            {
+                return write!(f, "{} {} {arg}", self.receiver, self.name.bright_red());
+            }
+        }
         write!(f, "{}", "(expr) ".yellow())?;
         write!(f, "{}.{}", self.receiver, self.name.blue())?;
         write!(f, "(")?;
@@ -367,9 +562,11 @@ impl Display for ExprMethodCall {
 impl Display for ExprObject {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", "(expr) ".yellow())?;
-        write!(f, "{}", "object { ".magenta())?;
-        for field in &self.fields {
-            write!(f, "{field} ")?;
+        write!(f, "{}", "object".magenta())?;
+        write_object_tail(f, self.superclass.as_ref(), &self.mixins)?;
+        write!(f, "{}", " { ".magenta())?;
+        for item in &self.body {
+            write!(f, "{item}; ")?;
         }
         write!(f, "{}", "}".magenta())
     }
@@ -397,9 +594,32 @@ impl Display for ExprTry {
     }
 }
 
+impl Display for ExprThrow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", "throw ".magenta(), self.expr)
+    }
+}
+
 impl Display for ExprTryBlock {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", "try { ... }".magenta()) // Simplified display for blocks
+        write!(f, "{}", "try { ... }".magenta())?; // Simplified display for blocks
+        for catch in &self.catches {
+            write!(f, " {catch}")?;
+        }
+        if self.always.is_some() {
+            write!(f, "{}", " then always { ... }".magenta())?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CatchClause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", "catch ".magenta(), self.param.name.cyan())?;
+        if let Some(kind) = &self.kind {
+            write!(f, " : {}", kind.blue())?;
+        }
+        write!(f, " {{ ... }}")
     }
 }
 
@@ -422,6 +642,18 @@ impl Display for ExprUnary {
     }
 }
 
+impl Display for ExprPropagate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.expr, "?".bright_red())
+    }
+}
+
+impl Display for ExprAssert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.expr, "!".bright_red())
+    }
+}
+
 impl Display for ExprSuper {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", "super".magenta())?;
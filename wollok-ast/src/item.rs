@@ -1,4 +1,7 @@
-use crate::expr::{Block, Expr};
+use crate::{
+    expr::{Block, Expr, ExprClass},
+    span::Spanned,
+};
 use owo_colors::OwoColorize;
 use std::fmt::Display;
 
@@ -12,28 +15,73 @@ pub enum Item {
     PrefixedMethod(ItemPrefixedMethod),
     Class(ItemClass),
     Object(ItemObject),
+    Mixin(ItemMixin),
     Import(ItemImport),
     Test(ItemTest),
+    Describe(ItemDescribe),
+    Fixture(ItemFixture),
     Program(ItemProgram),
     Package(ItemPackage),
+    /// Placeholder for an item that failed to parse
+    Error,
+}
+
+impl Item {
+    /// The doc comment of the item, for the items that can be documented
+    #[must_use]
+    pub fn doc(&self) -> Option<&str> {
+        match self {
+            Item::Property(ItemProperty { doc, .. })
+            | Item::Method(ItemMethod { doc, .. })
+            | Item::PrefixedMethod(ItemPrefixedMethod {
+                method: ItemMethod { doc, .. },
+                ..
+            })
+            | Item::Class(ItemClass { doc, .. })
+            | Item::Object(ItemObject { doc, .. }) => doc.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Attaches a doc comment, which other items just drop
+    pub(crate) fn with_doc(mut self, comment: Option<String>) -> Self {
+        match &mut self {
+            Item::Property(ItemProperty { doc, .. })
+            | Item::Method(ItemMethod { doc, .. })
+            | Item::PrefixedMethod(ItemPrefixedMethod {
+                method: ItemMethod { doc, .. },
+                ..
+            })
+            | Item::Class(ItemClass { doc, .. })
+            | Item::Object(ItemObject { doc, .. }) => *doc = comment,
+            _ => {}
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemConst {
-    pub name: String,
-    pub expr: Box<Expr>,
+    pub name: Spanned<String>,
+    pub expr: Box<Spanned<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemLet {
-    pub name: String,
-    pub expr: Box<Expr>,
+    pub name: Spanned<String>,
+    /// `None` for `let total`, which starts uninitialized
+    pub expr: Option<Box<Spanned<Expr>>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemProperty {
-    pub name: String,
-    pub expr: Box<Expr>,
+    pub name: Spanned<String>,
+    /// `None` for `property nombre`, usually initialized through `new`
+    pub expr: Option<Box<Spanned<Expr>>>,
+    /// Whether it was declared as `const property`
+    pub constant: bool,
+    /// Text of the `/** ... */` comment right before the declaration
+    pub doc: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -43,15 +91,39 @@ pub struct Ident {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Signature {
-    pub ident: String,
-    pub params: Vec<Ident>,
+    pub ident: Spanned<String>,
+    pub params: Vec<Spanned<Ident>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemMethod {
-    pub signature: Signature,
-    pub body: Block,
-    pub inline: bool,
+    pub signature: Spanned<Signature>,
+    pub body: MethodBody,
+    /// Text of the `/** ... */` comment right before the declaration
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MethodBody {
+    /// `method foo() { ... }`
+    Block(Spanned<Block>),
+    /// `method foo() = expr`
+    Inline(Spanned<Block>),
+    /// `method foo()` or `abstract method foo()`, left to the subclasses
+    Abstract,
+    /// `native method foo()`, provided by the runtime
+    Native,
+}
+
+impl MethodBody {
+    /// The statements of the method, if it has any
+    #[must_use]
+    pub fn block(&self) -> Option<&Spanned<Block>> {
+        match self {
+            Self::Block(block) | Self::Inline(block) => Some(block),
+            Self::Abstract | Self::Native => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -69,39 +141,114 @@ pub struct ItemPrefixedMethod {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemClass {
-    pub name: String,
-    pub superclass: Option<Vec<String>>,
-    pub body: Vec<Item>,
+    pub name: Spanned<String>,
+    pub superclass: Option<Vec<Spanned<String>>>,
+    /// Mixins composed with `with A, B`, in declaration order
+    pub mixins: Vec<Spanned<String>>,
+    pub body: Vec<Spanned<Item>>,
+    /// Text of the `/** ... */` comment right before the declaration
+    pub doc: Option<String>,
+}
+
+impl ItemClass {
+    /// Names of the attributes declared in the class body, in declaration order.
+    /// Inherited attributes are not included
+    pub fn attributes(&self) -> impl Iterator<Item = &str> {
+        attributes(&self.body)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemObject {
-    pub name: String,
-    pub body: Vec<Item>,
+    pub name: Spanned<String>,
+    /// `inherits Ave(energia = 10)`, with the arguments for the superclass
+    pub superclass: Option<Spanned<ExprClass>>,
+    pub mixins: Vec<Spanned<String>>,
+    pub body: Vec<Spanned<Item>>,
+    /// Text of the `/** ... */` comment right before the declaration
+    pub doc: Option<String>,
+}
+
+/// A reusable set of methods and state, as in `mixin Mostrable { ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemMixin {
+    pub name: Spanned<String>,
+    pub body: Vec<Spanned<Item>>,
+}
+
+impl ItemMixin {
+    /// Names of the attributes declared in the mixin body, in declaration order
+    pub fn attributes(&self) -> impl Iterator<Item = &str> {
+        attributes(&self.body)
+    }
+}
+
+/// Names of the `const`, `let` and `property` declarations of a body
+fn attributes(body: &[Spanned<Item>]) -> impl Iterator<Item = &str> {
+    body.iter().filter_map(|item| match &item.node {
+        Item::Const(ItemConst { name, .. })
+        | Item::Let(ItemLet { name, .. })
+        | Item::Property(ItemProperty { name, .. }) => Some(name.as_str()),
+        _ => None,
+    })
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemImport {
-    pub module: String,
+    /// The module path, as in `a.b` for `import a.b.*`
+    pub module: Spanned<String>,
     pub wildcard: bool, // true para "import modulo.*", false para imports específicos
+    /// Names selected from the module, empty for wildcard imports and for
+    /// a whole module as in `import trenes`
+    pub names: Vec<Spanned<ImportName>>,
+}
+
+/// A single imported name, as in `Clase` or `Console as Consola`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportName {
+    pub name: Spanned<String>,
+    pub alias: Option<Spanned<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemTest {
-    pub name: String,
-    pub body: Box<Expr>,
+    pub name: Spanned<String>,
+    /// `only test "..."`, runs this test alone
+    pub only: bool,
+    pub body: Spanned<Block>,
+}
+
+/// A group of tests, with its own declarations and fixtures
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemDescribe {
+    pub name: Spanned<String>,
+    pub body: Vec<Spanned<Item>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FixtureKind {
+    /// `setup { ... }`, runs before each test
+    Setup,
+    /// `after { ... }`, runs after each test
+    After,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemFixture {
+    pub kind: FixtureKind,
+    pub body: Spanned<Block>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemProgram {
-    pub name: String,
-    pub body: Box<Expr>,
+    pub name: Spanned<String>,
+    pub body: Spanned<Block>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ItemPackage {
-    pub name: String,
-    pub body: Vec<Item>,
+    pub name: Spanned<String>,
+    pub body: Vec<Spanned<Item>>,
 }
 
 impl Display for Item {
@@ -113,11 +260,15 @@ impl Display for Item {
             Item::Method(item) => write!(f, "{item}"),
             Item::Class(item) => write!(f, "{item}"),
             Item::Object(item) => write!(f, "{item}"),
+            Item::Mixin(item) => write!(f, "{item}"),
             Item::Import(item) => write!(f, "{item}"),
             Item::Test(item) => write!(f, "{item}"),
+            Item::Describe(item) => write!(f, "{item}"),
+            Item::Fixture(item) => write!(f, "{item}"),
             Item::Program(item) => write!(f, "{item}"),
             Item::Package(item) => write!(f, "{item}"),
             Item::PrefixedMethod(item) => write!(f, "{item}"),
+            Item::Error => write!(f, "{}", "<error>".red()),
         }
     }
 }
@@ -140,27 +291,30 @@ impl Display for ItemLet {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}{}{}{}{}",
+            "{}{}{}",
             "(item) ".yellow(),
             "let ".magenta(),
-            self.name.cyan(),
-            " = ".white(),
-            self.expr
-        )
+            self.name.cyan()
+        )?;
+        write_initializer(f, self.expr.as_deref())
     }
 }
 
 impl Display for ItemProperty {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let keyword = if self.constant {
+            "const property "
+        } else {
+            "property "
+        };
         write!(
             f,
-            "{}{}{}{}{}",
+            "{}{}{}",
             "(item) ".yellow(),
-            "property ".magenta(),
-            self.name.cyan(),
-            " = ".white(),
-            self.expr
-        )
+            keyword.magenta(),
+            self.name.cyan()
+        )?;
+        write_initializer(f, self.expr.as_deref())
     }
 }
 
@@ -186,19 +340,23 @@ impl Display for Signature {
 
 impl Display for ItemMethod {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.inline {
-            write!(
+        match &self.body {
+            MethodBody::Block(body) => {
+                write!(f, "{}{} {}", "method ".magenta(), self.signature, body)
+            }
+            MethodBody::Inline(body) => write!(
                 f,
                 "{}{} = {}",
                 "method ".magenta(),
                 self.signature,
-                self.body
-                    .stmts
+                body.stmts
                     .first()
                     .expect("Method body should have at least one statement")
-            )
-        } else {
-            write!(f, "{}{} {}", "method ".magenta(), self.signature, self.body)
+            ),
+            MethodBody::Abstract => {
+                write!(f, "{}{}", "abstract method ".magenta(), self.signature)
+            }
+            MethodBody::Native => write!(f, "{}{}", "native method ".magenta(), self.signature),
         }
     }
 }
@@ -212,6 +370,7 @@ impl Display for ItemClass {
                 write!(f, "{}, ", class.cyan())?;
             }
         }
+        write_mixins(f, &self.mixins)?;
         write!(f, "{{")?;
         for item in &self.body {
             write!(f, " {item}; ")?;
@@ -223,6 +382,7 @@ impl Display for ItemClass {
 impl Display for ItemObject {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}{}", "object ".magenta(), self.name.cyan())?;
+        write_object_tail(f, self.superclass.as_ref(), &self.mixins)?;
         writeln!(f, " {{")?;
         for item in &self.body {
             writeln!(f, "\t {item}; ")?;
@@ -231,19 +391,135 @@ impl Display for ItemObject {
     }
 }
 
+impl Display for ItemMixin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", "mixin ".magenta(), self.name.cyan())?;
+        writeln!(f, " {{")?;
+        for item in &self.body {
+            writeln!(f, "\t {item}; ")?;
+        }
+        writeln!(f, " }}")
+    }
+}
+
+fn write_initializer(
+    f: &mut std::fmt::Formatter<'_>,
+    expr: Option<&Spanned<Expr>>,
+) -> std::fmt::Result {
+    match expr {
+        Some(expr) => write!(f, "{}{}", " = ".white(), expr),
+        None => Ok(()),
+    }
+}
+
+fn write_superclass(
+    f: &mut std::fmt::Formatter<'_>,
+    superclass: Option<&Spanned<ExprClass>>,
+) -> std::fmt::Result {
+    let Some(superclass) = superclass else {
+        return Ok(());
+    };
+    write!(f, "{}{}(", " inherits ".magenta(), superclass.name.cyan())?;
+    let args = superclass.params.iter().map(ToString::to_string);
+    let named = superclass.named.iter().map(ToString::to_string);
+    write!(f, "{})", args.chain(named).collect::<Vec<_>>().join(", "))
+}
+
+fn write_mixins(f: &mut std::fmt::Formatter<'_>, mixins: &[Spanned<String>]) -> std::fmt::Result {
+    if mixins.is_empty() {
+        return Ok(());
+    }
+    let mixins = mixins.iter().map(|mixin| mixin.as_str());
+    write!(
+        f,
+        "{}{}",
+        " with ".magenta(),
+        mixins.collect::<Vec<_>>().join(", ").cyan()
+    )
+}
+
+/// Writes the part of an object shared by named and anonymous ones
+pub(crate) fn write_object_tail(
+    f: &mut std::fmt::Formatter<'_>,
+    superclass: Option<&Spanned<ExprClass>>,
+    mixins: &[Spanned<String>],
+) -> std::fmt::Result {
+    write_superclass(f, superclass)?;
+    write_mixins(f, mixins)
+}
+
 impl Display for ItemImport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}{}", "import ".magenta(), self.module.cyan())?;
         if self.wildcard {
             write!(f, ".*")?;
         }
+        match self.names.as_slice() {
+            [] => {}
+            [name] => write!(f, ".{name}")?,
+            names => {
+                write!(f, ".{{")?;
+                for (i, name) in names.iter().enumerate() {
+                    write!(f, "{name}")?;
+                    if i < names.len() - 1 {
+                        write!(f, ", ")?;
+                    }
+                }
+                write!(f, "}}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for ImportName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name.cyan())?;
+        if let Some(alias) = &self.alias {
+            write!(f, "{}{}", " as ".magenta(), alias.cyan())?;
+        }
         Ok(())
     }
 }
 
 impl Display for ItemTest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{} {}", "test ".magenta(), self.name.cyan(), self.body)
+        if self.only {
+            write!(f, "{}", "only ".magenta())?;
+        }
+        write!(
+            f,
+            "{}{} {}",
+            "test ".magenta(),
+            format!("{:?}", self.name).green(),
+            self.body
+        )
+    }
+}
+
+impl Display for ItemDescribe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            "describe ".magenta(),
+            format!("{:?}", self.name).green()
+        )?;
+        writeln!(f, " {{")?;
+        for item in &self.body {
+            writeln!(f, "\t {item}; ")?;
+        }
+        writeln!(f, " }}")
+    }
+}
+
+impl Display for ItemFixture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            FixtureKind::Setup => "setup ",
+            FixtureKind::After => "after ",
+        };
+        write!(f, "{}{}", kind.magenta(), self.body)
     }
 }
 
@@ -1,6 +1,9 @@
 pub mod ast;
+pub mod check;
+pub mod error;
 pub mod expr;
 pub mod item;
 pub mod parsers;
 pub mod source;
+pub mod span;
 pub mod utils;
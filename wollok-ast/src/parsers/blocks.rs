@@ -11,7 +11,14 @@ use wollok_lexer::{
     token::Token,
 };
 
-use crate::{ast::Stmt, expr::Block, source::Ast};
+use crate::{
+    ast::Stmt,
+    error::{Diagnostic, Result},
+    expr::{Block, Expr},
+    item::Item,
+    source::Ast,
+    span::Spanned,
+};
 
 impl Ast<'_> {
     /// Parses a method body block enclosed in braces, handling statements and expressions
@@ -31,48 +38,139 @@ impl Ast<'_> {
             }
 
             // Parse statement or expression
-            let stmt = self.parse_statement();
-            stmts.push(stmt);
+            let stmt = self.parse_statement_recovering();
+            stmts.push(self.reject_top_level_item(stmt));
         }
 
+        // A doc comment right before the closing brace documents nothing
+        self.pending_doc = None;
         Block { stmts }
     }
 
+    /// Tests, describes, programs, packages, imports and mixins can't be declared
+    /// inside a body. They are parsed as usual, reported and replaced by an error.
+    fn reject_top_level_item(&mut self, stmt: Stmt) -> Stmt {
+        let Stmt::Item(item) = &stmt else {
+            return stmt;
+        };
+        let msg = match item.node {
+            Item::Test(_) | Item::Describe(_) => {
+                "Tests can only be declared at the top level or inside a describe"
+            }
+            Item::Program(_) => "Programs can only be declared at the top level",
+            Item::Package(_) => "Packages can only be declared at the top level",
+            Item::Import(_) => "Imports can only be declared at the top level",
+            Item::Mixin(_) => "Mixins can only be declared at the top level",
+            _ => return stmt,
+        };
+        let span = item.span;
+        self.diagnostics
+            .push(Diagnostic::new(span, msg).with_label(span, "not allowed inside a body"));
+        Stmt::Item(Spanned::new(span, Item::Error))
+    }
+
+    /// Parses a `{ ... }` block, as used by tests, fixtures and programs.
+    /// As every block, its span includes the braces.
+    pub(crate) fn parse_braced_block(&mut self) -> Result<Spanned<Block>> {
+        let start = self.start_offset();
+        self.expect_token(&T!(OpenBrace))?;
+        let body = self.parse_block();
+        self.expect_token(&T!(CloseBrace))?;
+        Ok(self.spanned(start, body))
+    }
+
     /// Parses a single expression inside an inline method body (method = expr)
-    pub(crate) fn parse_inline_block(&mut self) -> Block {
+    pub(crate) fn parse_inline_block(&mut self) -> Result<Block> {
         trace!("Parsing inline block");
         let mut stmts = Vec::new();
 
         // Check if we hit a newline (end of inline block)
         if self.check(&T!(Newline)) {
             trace!("Empty inline block");
-            return Block { stmts };
+            return Ok(Block { stmts });
         }
 
         // Parse single expression
-        let stmt = self.parse_expr();
+        let stmt = self.parse_expr()?;
         trace!("Parsed statement: {:?}", stmt);
         stmts.push(Stmt::Expr(stmt));
 
-        Block { stmts }
+        Ok(Block { stmts })
     }
 
     /// Parses a single statement (can be a local declaration or an expression)
     /// Only allows `const` and `let` declarations, not `property` (which is class/object level)
-    pub(crate) fn parse_statement(&mut self) -> Stmt {
-        let token = self.peek_expect();
+    pub(crate) fn parse_statement(&mut self) -> Result<Stmt> {
+        let start = self.start_offset();
+        let doc = self.pending_doc.take();
+        if self.at_only_test() {
+            self.advance();
+            self.advance();
+            let test = self.parse_test(true)?;
+            return Ok(Stmt::Item(self.spanned(start, test)));
+        }
+        let named_object = self.at_named_object();
+        let token = self.peek_expect()?;
         match **token {
-            kw!(Object) => self.parse_object(),
-            kw!(Class) => self.parse_class(),
+            kw!(Object) if named_object => {
+                let object = self.parse_object()?.with_doc(doc);
+                Ok(Stmt::Item(self.spanned(start, object)))
+            }
+            kw!(Import) => {
+                let import = self.parse_import()?;
+                Ok(Stmt::Item(self.spanned(start, import)))
+            }
+            kw!(Describe) => {
+                let describe = self.parse_describe()?;
+                Ok(Stmt::Item(self.spanned(start, describe)))
+            }
+            kw!(Test) => {
+                let test = self.parse_test(false)?;
+                Ok(Stmt::Item(self.spanned(start, test)))
+            }
+            kw!(Program) => {
+                let program = self.parse_program()?;
+                Ok(Stmt::Item(self.spanned(start, program)))
+            }
+            kw!(Package) => {
+                let package = self.parse_package()?;
+                Ok(Stmt::Item(self.spanned(start, package)))
+            }
+            kw!(Mixin) => {
+                let mixin = self.parse_mixin()?;
+                Ok(Stmt::Item(self.spanned(start, mixin)))
+            }
+            kw!(Class) => {
+                let class = self.parse_class()?.with_doc(doc);
+                Ok(Stmt::Item(self.spanned(start, class)))
+            }
             Token::Keyword(kw!(@raw Let) | kw!(@raw Const)) => {
                 token.recover();
-                Stmt::Item(self.parse_item())
+                Ok(Stmt::Item(self.parse_item()?))
             }
             _ => {
                 // If it's not a declaration keyword, try to parse it as an expression
                 token.recover();
-                let expr = self.parse_expr();
-                Stmt::Expr(expr)
+                let expr = self.parse_expr()?;
+                Ok(Stmt::Expr(expr))
+            }
+        }
+    }
+
+    /// Parses a single statement, replacing it with an error placeholder
+    /// and synchronizing on the next statement if it fails
+    pub(crate) fn parse_statement_recovering(&mut self) -> Stmt {
+        let start = self.start_offset();
+        let is_item = self.at_declaration();
+        match self.parse_statement() {
+            Ok(stmt) => stmt,
+            Err(diagnostic) => {
+                self.recover(diagnostic);
+                if is_item {
+                    Stmt::Item(self.spanned(start, Item::Error))
+                } else {
+                    Stmt::Expr(self.spanned(start, Expr::Error))
+                }
             }
         }
     }
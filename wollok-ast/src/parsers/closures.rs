@@ -0,0 +1,86 @@
+/// Closure parsing utilities
+///
+/// This module contains all closure-related parsing logic, including:
+/// - Block closures (`{ a, b -> body }` and `{ body }`)
+/// - Arrow closures (`(a, b) => expr`, `() => expr` and `n => expr`)
+use tracing::debug;
+use wollok_lexer::{macros::T, token::Token};
+
+use crate::{
+    error::Result,
+    expr::{Expr, ExprClosure},
+    item::Ident,
+    source::Ast,
+    span::Spanned,
+};
+
+impl Ast<'_> {
+    /// Parses a block closure, right after the opening `{` found at `start`.
+    /// The body shares the braces of the closure, so its span includes them.
+    pub(crate) fn parse_block_closure(&mut self, start: usize) -> Result<Expr> {
+        let params = if self.params_ahead(&T!(Arrow)).is_some() {
+            self.parse_identifier_list(&T!(Arrow))?
+        } else {
+            Vec::new()
+        };
+        debug!("Parsing block closure with {} parameters", params.len());
+
+        let body = self.parse_block();
+        self.expect_token(&T!(CloseBrace))?;
+        let body = self.spanned(start, body);
+
+        Ok(Expr::Closure(ExprClosure { params, body }))
+    }
+
+    /// Parses an arrow closure with a parameter list, right after the opening `(`
+    pub(crate) fn parse_arrow_closure(&mut self) -> Result<Expr> {
+        let params = self.parse_identifier_list(&T!(CloseParen))?;
+        debug!("Parsing arrow closure with {} parameters", params.len());
+        self.expect_token(&T!(FatArrow))?;
+        let body = self.parse_branch()?;
+
+        Ok(Expr::Closure(ExprClosure { params, body }))
+    }
+
+    /// Parses a single parameter arrow closure (`n => expr`), right after the parameter
+    pub(crate) fn parse_unary_closure(&mut self, param: Spanned<Ident>) -> Result<Expr> {
+        debug!("Parsing arrow closure with parameter '{}'", param.name);
+        self.expect_token(&T!(FatArrow))?;
+        let body = self.parse_branch()?;
+
+        Ok(Expr::Closure(ExprClosure {
+            params: vec![param],
+            body,
+        }))
+    }
+
+    /// Checks whether the tokens after an opening `(` are the parameters of an arrow closure
+    pub(crate) fn at_arrow_closure(&self) -> bool {
+        self.params_ahead(&T!(CloseParen))
+            .and_then(|idx| self.tokens.get(idx))
+            .is_some_and(|token| token.token == T!(FatArrow))
+    }
+
+    /// Looks ahead for `ident (, ident)* terminator` or a lone `terminator`
+    /// without consuming anything, returning the position right after the terminator
+    fn params_ahead(&self, terminator: &Token) -> Option<usize> {
+        if self.tokens.front()?.token == *terminator {
+            return Some(1);
+        }
+
+        let mut idx = 0;
+        loop {
+            if !matches!(self.tokens.get(idx)?.token, Token::Ident(_)) {
+                return None;
+            }
+            let next = &self.tokens.get(idx + 1)?.token;
+            if next == terminator {
+                return Some(idx + 2);
+            }
+            if *next != T!(Comma) {
+                return None;
+            }
+            idx += 2;
+        }
+    }
+}
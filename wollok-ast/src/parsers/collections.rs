@@ -9,27 +9,31 @@ use tracing::debug;
 use wollok_lexer::macros::T;
 
 use crate::{
+    error::Result,
     expr::{Expr, ExprArray, ExprSet},
     source::Ast,
 };
 
 impl Ast<'_> {
     /// Parses array expressions starting with [
-    pub(crate) fn parse_array(&mut self) -> Expr {
+    pub(crate) fn parse_array(&mut self) -> Result<Expr> {
         debug!("Parsing array expression");
-        let elements =
-            self.parse_separated_list(Ast::parse_element_expr, &T!(Comma), &T!(CloseSquareBracket));
+        let elements = self.parse_separated_list(
+            Ast::parse_element_expr,
+            &T!(Comma),
+            &T!(CloseSquareBracket),
+        )?;
         debug!("Parsed array with {} elements", elements.len());
-        Expr::Array(ExprArray { elements })
+        Ok(Expr::Array(ExprArray { elements }))
     }
 
     /// Parses set expressions starting with #
-    pub(crate) fn parse_set(&mut self) -> Expr {
+    pub(crate) fn parse_set(&mut self) -> Result<Expr> {
         debug!("Parsing set expression");
-        self.expect_token(&T!(OpenBrace));
+        self.expect_token(&T!(OpenBrace))?;
         let elements =
-            self.parse_separated_list(Ast::parse_element_expr, &T!(Comma), &T!(CloseBrace));
+            self.parse_separated_list(Ast::parse_element_expr, &T!(Comma), &T!(CloseBrace))?;
         debug!("Parsed set with {} elements", elements.len());
-        Expr::Set(ExprSet { elements })
+        Ok(Expr::Set(ExprSet { elements }))
     }
 }
@@ -0,0 +1,171 @@
+/// Control flow parsing utilities
+///
+/// This module contains all control flow parsing logic, including:
+/// - Conditional expressions (`if (cond) expr else expr`)
+/// - Block and inline branches
+/// - `else if` chains
+/// - Return expressions (`return expr` and bare `return`)
+/// - Exceptions (`throw`, `try expr` and `try { } catch e : Type { } then always { }`)
+use tracing::debug;
+use wollok_lexer::{
+    macros::{T, kw},
+    token::Token,
+};
+
+use crate::{
+    ast::Stmt,
+    error::Result,
+    expr::{
+        Block, CatchClause, Expr, ExprBlock, ExprIf, ExprReturn, ExprThrow, ExprTry, ExprTryBlock,
+    },
+    item::Ident,
+    source::Ast,
+    span::Spanned,
+};
+
+impl Ast<'_> {
+    /// Parses an `if` expression, right after the `if` keyword
+    pub(crate) fn parse_if(&mut self) -> Result<Expr> {
+        debug!("Parsing if expression");
+        self.expect_token(&T!(OpenParen))?;
+        let condition = Box::new(self.parse_expr()?);
+        self.expect_token(&T!(CloseParen))?;
+        let then = self.parse_branch()?;
+
+        // `else` may be written on the next line
+        if !self.check_past_trivia(&kw!(Else)) {
+            return Ok(Expr::If(ExprIf {
+                condition,
+                then,
+                otherwise: None,
+            }));
+        }
+        self.skip_trivia();
+        self.expect_token(&kw!(Else))?;
+
+        let start = self.start_offset();
+        let otherwise = if self.consume(&kw!(If)) {
+            let chained = self.parse_if()?;
+            self.spanned(start, chained)
+        } else {
+            self.parse_branch()?
+                .map(|block| Expr::Block(ExprBlock { block }))
+        };
+
+        Ok(Expr::If(ExprIf {
+            condition,
+            then,
+            otherwise: Some(Box::new(otherwise)),
+        }))
+    }
+
+    /// Parses a `return` expression, right after the `return` keyword.
+    /// The value is omitted when nothing else follows on the same line.
+    pub(crate) fn parse_return(&mut self) -> Result<Expr> {
+        let bare = matches!(
+            self.peek_token(),
+            None | Some(
+                Token::Comment(_)
+                    | Token::DocComment(_)
+                    | Token::Punctuation(
+                        T!(@raw Newline) | T!(@raw CloseBrace) | T!(@raw CloseParen)
+                    )
+                    | kw!(Else)
+            )
+        );
+        debug!("Parsing return expression (bare: {bare})");
+
+        let value = if bare {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+        Ok(Expr::Return(ExprReturn { value }))
+    }
+
+    /// Parses a `throw` expression, right after the `throw` keyword
+    pub(crate) fn parse_throw(&mut self) -> Result<Expr> {
+        let expr = Box::new(self.parse_expr()?);
+        Ok(Expr::Throw(ExprThrow { expr }))
+    }
+
+    /// Parses either `try expr` or a `try { ... }` block with its handlers,
+    /// right after the `try` keyword
+    pub(crate) fn parse_try(&mut self) -> Result<Expr> {
+        let start = self.start_offset();
+        if !self.consume(&T!(OpenBrace)) {
+            debug!("Parsing try expression");
+            let expr = Box::new(self.parse_expr()?);
+            return Ok(Expr::Try(ExprTry { expr }));
+        }
+
+        debug!("Parsing try block");
+        let block = self.parse_block();
+        self.expect_token(&T!(CloseBrace))?;
+        let block = self.spanned(start, block);
+
+        // Handlers may be written on the following lines
+        let mut catches = Vec::new();
+        while self.check_past_trivia(&kw!(Catch)) {
+            self.skip_trivia();
+            catches.push(self.parse_catch()?);
+        }
+
+        // `then always` is only special here, anywhere else they are names
+        let then = Token::Ident(String::from("then"));
+        let always = if self.check_past_trivia(&then) {
+            self.skip_trivia();
+            self.expect_token(&then)?;
+            self.expect_token(&Token::Ident(String::from("always")))?;
+            Some(self.parse_branch()?)
+        } else {
+            None
+        };
+
+        if catches.is_empty() && always.is_none() {
+            return self.error_in_place("Expected `catch` or `then always` after try block");
+        }
+
+        Ok(Expr::TryBlock(ExprTryBlock {
+            block,
+            catches,
+            always,
+        }))
+    }
+
+    /// Parses a `catch e : Type { ... }` clause, where the type is optional
+    fn parse_catch(&mut self) -> Result<Spanned<CatchClause>> {
+        let start = self.start_offset();
+        self.expect_token(&kw!(Catch))?;
+        let param = self.expect_match("Expected exception identifier", |t| {
+            let name = t.into_ident()?;
+            Some(Spanned::new(t.span, Ident { name }))
+        })?;
+        let kind = if self.consume(&T!(Colon)) {
+            Some(self.parse_qualified_name()?)
+        } else {
+            None
+        };
+        let body = self.parse_branch()?;
+
+        let clause = CatchClause { param, kind, body };
+        Ok(self.spanned(start, clause))
+    }
+
+    /// Parses the body of a branch, either a `{ ... }` block or a single expression
+    pub(crate) fn parse_branch(&mut self) -> Result<Spanned<Block>> {
+        self.skip_trivia();
+        let start = self.start_offset();
+        if self.consume(&T!(OpenBrace)) {
+            let block = self.parse_block();
+            self.expect_token(&T!(CloseBrace))?;
+            return Ok(self.spanned(start, block));
+        }
+
+        let expr = self.parse_expr()?;
+        let block = Block {
+            stmts: vec![Stmt::Expr(expr)],
+        };
+        Ok(self.spanned(start, block))
+    }
+}
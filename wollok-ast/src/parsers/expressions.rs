@@ -5,74 +5,137 @@
 /// - Assignment expressions
 /// - Field access expressions
 use tracing::{debug, trace};
-use wollok_common::ast::BinaryOp;
+use wollok_common::ast::{BinaryOp, UnaryOp};
 use wollok_lexer::{
     macros::{T, kw},
-    token::Token,
+    token::{Span, Token},
 };
 
 use crate::{
-    expr::{Expr, ExprAssign, ExprBinary, ExprCall, ExprField, ExprLit},
+    error::Result,
+    expr::{
+        Expr, ExprAssert, ExprAssign, ExprCall, ExprClass, ExprField, ExprLit, ExprMethodCall,
+        ExprPropagate, ExprSuper, ExprUnary, NamedArg,
+    },
+    item::Ident,
     source::Ast,
+    span::Spanned,
 };
 
 impl Ast<'_> {
     /// Parses a complete expression, handling assignments and primary expressions
-    pub(crate) fn parse_expr(&mut self) -> Expr {
+    pub(crate) fn parse_expr(&mut self) -> Result<Spanned<Expr>> {
         self.parse_assignment_expr()
     }
 
     /// Parses assignment expressions (e.g., `variable = value`)
-    pub(crate) fn parse_assignment_expr(&mut self) -> Expr {
-        let expr = self.parse_primary_expr();
+    pub(crate) fn parse_assignment_expr(&mut self) -> Result<Spanned<Expr>> {
+        let expr = self.parse_primary_expr()?;
 
         // Check if this is an assignment
         if self.consume(&T!(Equals)) {
-            let value = Box::new(self.parse_expr());
+            let value = Box::new(self.parse_expr()?);
             // Create assignment expression
-            return Expr::Assign(ExprAssign {
+            let start = expr.span.from;
+            let assign = Expr::Assign(ExprAssign {
                 left: Box::new(expr),
                 right: value,
             });
+            return Ok(self.spanned(start, assign));
         }
 
-        expr
+        Ok(expr)
     }
 
     /// Parses primary expressions (literals, identifiers, collections, etc.)
-    pub(crate) fn parse_primary_expr(&mut self) -> Expr {
-        let expr = self.parse_postfix_expr();
+    pub(crate) fn parse_primary_expr(&mut self) -> Result<Spanned<Expr>> {
+        let expr = self.parse_unary_expr()?;
         self.parse_binary_expr(expr, 0)
     }
 
+    /// Parses prefix operators (`!`, `not`, `-`, `+`), which bind tighter than
+    /// any binary operator but looser than message sends: `-a.b()` is `-(a.b())`
+    pub(crate) fn parse_unary_expr(&mut self) -> Result<Spanned<Expr>> {
+        let start = self.start_offset();
+        let op = match self.peek_token() {
+            Some(T!(Bang) | kw!(Not)) => UnaryOp::Not,
+            Some(T!(Minus)) => UnaryOp::Neg,
+            Some(T!(Plus)) => UnaryOp::Plus,
+            _ => return self.parse_postfix_expr(),
+        };
+
+        self.advance(); // consume operator
+        let operand = self.parse_unary_expr()?;
+        let unary = Expr::Unary(ExprUnary {
+            op,
+            expr: Box::new(operand),
+        });
+        Ok(self.spanned(start, unary))
+    }
+
     /// Parses postfix expressions (function calls, field access, etc.)
     /// Only allows calls on callable expressions (identifiers, field access, parentheses)
-    pub(crate) fn parse_postfix_expr(&mut self) -> Expr {
-        let mut expr = self.parse_atomic_expr();
+    pub(crate) fn parse_postfix_expr(&mut self) -> Result<Spanned<Expr>> {
+        let mut expr = self.parse_atomic_expr()?;
+        let start = expr.span.from;
 
         loop {
             if self.check(&T!(OpenParen)) && Self::is_callable(&expr) {
-                // Function call: expr() - but only if expr is callable
-                let args = self.parse_params();
-                expr = Expr::Call(ExprCall {
-                    callee: Box::new(expr),
-                    args,
-                });
+                let args = self.parse_params()?;
+                let call = match expr.node {
+                    // Message send: receiver.selector(args), where a bare
+                    // `foo()` is sent to the implicit `self`
+                    Expr::Field(ExprField { base, name }) => Expr::MethodCall(ExprMethodCall {
+                        receiver: base,
+                        name,
+                        args,
+                    }),
+                    // Any other callable value: expr(args)
+                    _ => Expr::Call(ExprCall {
+                        callee: Box::new(expr),
+                        args,
+                    }),
+                };
+                expr = self.spanned(start, call);
             } else if self.check(&T!(Dot)) {
                 // Field access: expr.field
                 self.advance(); // consume the dot
-                let field_name = self.expect_match("Expected field name", |t| t.into_ident());
-                expr = Expr::Field(ExprField {
+                let field_name = self.expect_match("Expected field name", |t| {
+                    Some(Spanned::new(t.span, t.into_selector()?))
+                })?;
+                let field = Expr::Field(ExprField {
                     name: field_name,
                     base: Box::new(expr),
                 });
+                expr = self.spanned(start, field);
+            } else if self.consume(&T!(Question)) {
+                // Error propagation: expr?
+                let propagate = Expr::Propagate(ExprPropagate {
+                    expr: Box::new(expr),
+                });
+                expr = self.spanned(start, propagate);
+            } else if self.at_postfix_bang() {
+                // Assertion: expr!
+                self.advance(); // consume the bang
+                let assert = Expr::Assert(ExprAssert {
+                    expr: Box::new(expr),
+                });
+                expr = self.spanned(start, assert);
             } else {
                 // No more postfix operations
                 break;
             }
         }
 
-        expr
+        Ok(expr)
+    }
+
+    /// A `!` is an assertion only when written right after the expression,
+    /// as in `dividir(a, b)!`; otherwise it is the prefix negation of whatever follows
+    fn at_postfix_bang(&self) -> bool {
+        self.tokens
+            .front()
+            .is_some_and(|token| token.token == T!(Bang) && token.span.from == self.last_offset)
     }
 
     /// Determines if an expression can be called (i.e., can have () after it)
@@ -81,8 +144,8 @@ impl Ast<'_> {
             expr,
             // Identifiers can be called: foo()
             Expr::Field(_) |
-            // Method calls can be chained: obj.method1().method2()
-            Expr::Call(_) |
+            // Calls can be chained: obj.method1()()
+            Expr::Call(_) | Expr::MethodCall(_) |
             // Object instantiation can be called: new Foo().method()
             Expr::Class(_) |
             // Self can be called: self()
@@ -91,77 +154,190 @@ impl Ast<'_> {
     }
 
     /// Parses atomic expressions without binary operations
-    pub(crate) fn parse_atomic_expr(&mut self) -> Expr {
-        let token = self.expect();
+    pub(crate) fn parse_atomic_expr(&mut self) -> Result<Spanned<Expr>> {
+        let token = self.expect()?;
         trace!("Parsing atomic expression with token: {:?}", *token);
         self.skip_comments();
 
-        match *token {
-            Token::Ident(ref ident) => Expr::Field(ExprField {
-                name: ident.clone(),
-                base: Box::new(Expr::Self_),
-            }),
-            kw!(New) => {
-                let name = self.expect_match("Expected class name", |t| t.into_ident());
-                let params = self.parse_params();
-                Expr::Class(crate::expr::ExprClass { name, params })
+        let start = token.span.from;
+        let expr = match *token {
+            Token::Ident(ref ident) if self.check(&T!(FatArrow)) => {
+                let param = Spanned::new(
+                    token.span,
+                    Ident {
+                        name: ident.clone(),
+                    },
+                );
+                self.parse_unary_closure(param)?
+            }
+            Token::Ident(ref ident) => Self::implicit_field(ident, token.span),
+            // `assert` is the well known object of the test library
+            kw!(Assert) => Self::implicit_field("assert", token.span),
+            kw!(New) => self.parse_new()?,
+            kw!(Object) => self.parse_anonymous_object()?,
+            kw!(This) => Expr::Self_,
+            kw!(Super) => {
+                // `super` always sends the overridden method, so it is always called
+                let args = self.parse_params()?;
+                Expr::Super(ExprSuper { args })
             }
             Token::Literal(ref lit) => Expr::Lit(ExprLit { value: lit.clone() }),
-            T!(OpenSquareBracket) => self.parse_array(),
-            T!(Hash) => self.parse_set(),
-            T!(OpenParen) => self.parse_parenthesized_expr(),
-            _ => self.error_in_place("Expected expression"),
+            T!(OpenSquareBracket) => self.parse_array()?,
+            T!(Hash) => self.parse_set()?,
+            T!(OpenParen) if self.at_arrow_closure() => self.parse_arrow_closure()?,
+            T!(OpenParen) => self.parse_parenthesized_expr()?,
+            T!(OpenBrace) => self.parse_block_closure(start)?,
+            kw!(If) => self.parse_if()?,
+            kw!(Return) => self.parse_return()?,
+            kw!(Throw) => self.parse_throw()?,
+            kw!(Try) => self.parse_try()?,
+            _ => return self.error_in_place("Expected expression"),
+        };
+
+        Ok(self.spanned(start, expr))
+    }
+
+    /// A bare identifier, sent to the implicit `self`
+    fn implicit_field(name: &str, span: Span) -> Expr {
+        Expr::Field(ExprField {
+            name: Spanned::new(span, name.to_owned()),
+            // The receiver is implicit, so it has no width
+            base: Box::new(Spanned::new(
+                Span {
+                    from: span.from,
+                    to: span.from,
+                },
+                Expr::Self_,
+            )),
+        })
+    }
+
+    /// Parses an instantiation after `new`, either with positional arguments
+    /// (`new Libro(1, 2)`) or named initializers (`new Ave(energia = 10)`)
+    fn parse_new(&mut self) -> Result<Expr> {
+        let name = self.expect_match("Expected class name", |t| t.into_ident())?;
+        Ok(Expr::Class(self.parse_instantiation(name)?))
+    }
+
+    /// Parses the arguments used to build an instance of `name`, also used by
+    /// objects that inherit from a class: `object pepita inherits Ave(energia = 10)`
+    pub(crate) fn parse_instantiation(&mut self, name: String) -> Result<ExprClass> {
+        if !self.at_named_args() {
+            let params = self.parse_params()?;
+            return Ok(ExprClass {
+                name,
+                params,
+                named: Vec::new(),
+            });
         }
+
+        self.expect_token(&T!(OpenParen))?;
+        let named = self.parse_separated_list(Ast::parse_named_arg, &T!(Comma), &T!(CloseParen))?;
+        debug!("Parsed {} named arguments for '{}'", named.len(), name);
+        Ok(ExprClass {
+            name,
+            params: Vec::new(),
+            named,
+        })
+    }
+
+    /// Parses a single `attribute = value` initializer
+    fn parse_named_arg(&mut self) -> Result<Spanned<NamedArg>> {
+        self.skip_trivia();
+        let start = self.start_offset();
+        let name = self.parse_name("Expected a named argument")?;
+        self.expect_token(&T!(Equals))?;
+        let value = self.parse_primary_expr()?;
+        self.skip_trivia();
+        Ok(self.spanned(start, NamedArg { name, value }))
+    }
+
+    /// Whether the arguments of `new` start as `(name = ...`
+    fn at_named_args(&self) -> bool {
+        let mut tokens = self.tokens.iter().filter(|t| {
+            !matches!(
+                t.token,
+                Token::Comment(_) | Token::DocComment(_) | T!(Newline)
+            )
+        });
+        matches!(tokens.next(), Some(t) if t.token == T!(OpenParen))
+            && matches!(tokens.next(), Some(t) if matches!(t.token, Token::Ident(_)))
+            && matches!(tokens.next(), Some(t) if t.token == T!(Equals))
     }
 
     /// Parse binary expressions using precedence climbing
-    pub(crate) fn parse_binary_expr(&mut self, mut lhs: Expr, min_prec: u8) -> Expr {
-        while let Some((op, prec, right_assoc)) = self.peek_operator() {
+    pub(crate) fn parse_binary_expr(
+        &mut self,
+        mut lhs: Spanned<Expr>,
+        min_prec: u8,
+    ) -> Result<Spanned<Expr>> {
+        let start = lhs.span.from;
+        while let Some((_, prec, right_assoc)) = self.peek_operator() {
             if prec < min_prec {
                 break;
             }
 
-            self.advance(); // consume operator
+            // The selector is the operator as written, so `and` and `&&` are different messages
+            let Some(operator) = self.advance() else {
+                break;
+            };
+            let selector = Spanned::new(operator.span, operator.token.to_string());
             let next_prec = if right_assoc { prec } else { prec + 1 };
-            let rhs_atomic = self.parse_postfix_expr();
-            let rhs = self.parse_binary_expr(rhs_atomic, next_prec);
+            let rhs_atomic = self.parse_unary_expr()?;
+            let rhs = self.parse_binary_expr(rhs_atomic, next_prec)?;
 
-            lhs = Expr::Binary(ExprBinary {
-                left: Box::new(lhs),
-                right: Box::new(rhs),
-                op,
-            });
+            let send = Expr::MethodCall(ExprMethodCall::binary(lhs, selector, rhs));
+            lhs = self.spanned(start, send);
         }
-        lhs
+        Ok(lhs)
     }
 
-    /// Peek at the next operator, returning (`BinaryOp`, precedence)
+    /// Peek at the next operator, returning (`BinaryOp`, precedence, right associativity)
+    ///
+    /// Follows Wollok's precedence table, from loosest to tightest binding:
+    /// `|| or`, `&& and`, `=== !== == !=`, `>= <= > <`, `+ -`, `* /`, `** %`
     fn peek_operator(&mut self) -> Option<(BinaryOp, u8, bool)> {
         self.peek().and_then(|peeked| {
-            let token = &peeked.token.token;
-            let result = match token {
-                T!(Multiply) => Some((BinaryOp::Multiply, 4, false)),
-                T!(Div) => Some((BinaryOp::Div, 4, false)),
-                T!(Plus) => Some((BinaryOp::Plus, 2, false)),
-                T!(Minus) => Some((BinaryOp::Minus, 2, false)),
-                _ => None,
-            };
+            let result = Self::binary_operator(&peeked.token.token);
             peeked.recover();
             result
         })
     }
 
+    /// The operator a token stands for, with its precedence and right associativity
+    pub(crate) fn binary_operator(token: &Token) -> Option<(BinaryOp, u8, bool)> {
+        match token {
+            T!(Or) | kw!(Or) => Some((BinaryOp::Or, 1, false)),
+            T!(And) | kw!(And) => Some((BinaryOp::And, 2, false)),
+            T!(StrictEq) => Some((BinaryOp::StrictEq, 3, false)),
+            T!(StrictNe) => Some((BinaryOp::StrictNe, 3, false)),
+            T!(Eq) => Some((BinaryOp::Eq, 3, false)),
+            T!(Ne) => Some((BinaryOp::Ne, 3, false)),
+            T!(Ge) => Some((BinaryOp::Ge, 4, false)),
+            T!(Le) => Some((BinaryOp::Le, 4, false)),
+            T!(Gt) => Some((BinaryOp::Gt, 4, false)),
+            T!(Lt) => Some((BinaryOp::Lt, 4, false)),
+            T!(Plus) => Some((BinaryOp::Plus, 5, false)),
+            T!(Minus) => Some((BinaryOp::Minus, 5, false)),
+            T!(Multiply) => Some((BinaryOp::Multiply, 6, false)),
+            T!(Div) => Some((BinaryOp::Div, 6, false)),
+            T!(Pow) => Some((BinaryOp::Pow, 7, false)),
+            T!(Modulo) => Some((BinaryOp::Modulo, 7, false)),
+            _ => None,
+        }
+    }
+
     /// Parses expressions enclosed in parentheses
-    pub(crate) fn parse_parenthesized_expr(&mut self) -> Expr {
+    pub(crate) fn parse_parenthesized_expr(&mut self) -> Result<Expr> {
         debug!("Parsing the parenthized expr");
-        let expr = self.parse_expr();
-        self.expect_token(&T!(CloseParen));
-        expr // For now, we just return the inner expression
+        let expr = self.parse_expr()?;
+        self.expect_token(&T!(CloseParen))?;
+        Ok(expr.node) // For now, we just return the inner expression
     }
 
     /// Parses a single expression element (for arrays, sets, etc.)
     /// This is now unified with primary expression logic
-    pub(crate) fn parse_element_expr(&mut self) -> Expr {
+    pub(crate) fn parse_element_expr(&mut self) -> Result<Spanned<Expr>> {
         self.parse_primary_expr()
     }
 }
@@ -2,120 +2,204 @@
 ///
 /// This module contains all item-related parsing logic, including:
 /// - Object declarations
+/// - Mixin declarations and `with` composition
 /// - Method declarations and signatures
 /// - Property declarations
 /// - Const and let declarations
+/// - Import declarations
+/// - Program and package declarations
 use tracing::{debug, info, trace, warn};
-use wollok_lexer::macros::{T, kw};
+use wollok_lexer::{
+    macros::{T, kw},
+    token::{Span, Token},
+};
 
 use crate::{
     ast::Stmt,
-    expr::Expr,
+    error::{Diagnostic, Result},
+    expr::{Expr, ExprClass, ExprObject},
     item::{
-        Item, ItemClass, ItemConst, ItemLet, ItemMethod, ItemObject, ItemPrefixedMethod,
-        ItemProperty, Prefix, Signature,
+        ImportName, Item, ItemClass, ItemConst, ItemImport, ItemLet, ItemMethod, ItemMixin,
+        ItemObject, ItemPackage, ItemPrefixedMethod, ItemProgram, ItemProperty, MethodBody, Prefix,
+        Signature,
     },
     source::Ast,
+    span::Spanned,
 };
 
 impl Ast<'_> {
-    fn parse_override(&mut self) -> (Item, Prefix) {
-        let item = self.parse_item();
-        if self.consume(&kw!(Fallible)) {
+    fn parse_override(&mut self) -> Result<(Item, Prefix)> {
+        let prefix = if self.consume(&kw!(Fallible)) {
             info!("Entering on fallible method");
-            (item, Prefix::OverrideFallible)
+            Prefix::OverrideFallible
         } else {
-            (item, Prefix::Override)
-        }
+            Prefix::Override
+        };
+        Ok((self.parse_item()?.node, prefix))
     }
 
-    pub(crate) fn parse_class_item(&mut self) -> Item {
+    pub(crate) fn parse_class_item(&mut self) -> Result<Spanned<Item>> {
         self.skip_comments();
+        let start = self.start_offset();
         if self.consume(&kw!(Override)) {
             info!("Entering on override item");
-            let (item, prefix) = self.parse_override();
+            let (item, prefix) = self.parse_override()?;
             let Item::Method(method) = item else {
-                self.error_in_place("expected a method");
+                return self.error_in_place("expected a method");
             };
-            Item::PrefixedMethod(ItemPrefixedMethod { prefix, method })
+            let item = Item::PrefixedMethod(ItemPrefixedMethod { prefix, method });
+            Ok(self.spanned(start, item))
         } else if self.consume(&kw!(Fallible)) {
-            let Item::Method(method) = self.parse_item() else {
-                self.error_in_place("expected a method");
+            let Item::Method(method) = self.parse_item()?.node else {
+                return self.error_in_place("expected a method");
             };
-            Item::PrefixedMethod(ItemPrefixedMethod {
+            let item = Item::PrefixedMethod(ItemPrefixedMethod {
                 prefix: Prefix::Fallible,
                 method,
-            })
+            });
+            Ok(self.spanned(start, item))
         } else {
             self.parse_item()
         }
     }
 
     /// Parses items (const, let, property, method declarations)
-    pub(crate) fn parse_item(&mut self) -> Item {
+    pub(crate) fn parse_item(&mut self) -> Result<Spanned<Item>> {
         self.skip_comments();
-        let item = self.expect();
+        let doc = self.pending_doc.take();
+        let start = self.start_offset();
+        let item = self.expect()?;
         debug!("Parsing item: {:?}", *item);
-        match *item {
+        let item = match *item {
+            kw!(Const) if self.consume(&kw!(Property)) => self.parse_property(true)?,
+            kw!(Let) if self.consume(&kw!(Property)) => self.parse_property(false)?,
             kw!(Const) => {
                 trace!("Parsing const declaration");
-                let name = self.expect_match("Expected object identifier", |t| t.into_ident()); // Here we should expect the object ident.
-                self.expect_token(&T!(Equals));
-                let expr = Box::new(self.parse_expr());
+                let name = self.parse_name("Expected object identifier")?; // Here we should expect the object ident.
+                self.expect_token(&T!(Equals))?;
+                let expr = Box::new(self.parse_expr()?);
                 debug!("Parsed const '{}' with expression", name);
                 Item::Const(ItemConst { name, expr })
             }
             kw!(Let) => {
                 trace!("Parsing let declaration");
-                let name = self.expect_match("Expected object identifier", |t| t.into_ident()); // Here we should expect the object ident.
-                self.expect_token(&T!(Equals));
-                let expr = Box::new(self.parse_expr());
-                debug!("Parsed let '{}' with expression", name);
+                let name = self.parse_name("Expected object identifier")?; // Here we should expect the object ident.
+                let expr = self.parse_initializer()?;
+                debug!("Parsed let '{}' (initialized: {})", name, expr.is_some());
                 Item::Let(ItemLet { name, expr })
             }
-            kw!(Property) => {
-                trace!("Parsing property declaration");
-                let name = self.expect_match("Expected object identifier", |t| t.into_ident()); // Here we should expect the object ident.
-                self.expect_token(&T!(Equals));
-                let expr = Box::new(self.parse_expr());
-                debug!("Parsed property '{}' with expression", name);
-                Item::Property(ItemProperty { name, expr })
-            }
+            kw!(Property) => self.parse_property(false)?,
             kw!(Method) => {
                 trace!("Parsing method declaration");
-                let signature = self.parse_method_signature();
+                let signature = self.parse_method_signature()?;
+                let body_start = self.start_offset();
 
-                if self.consume(&T!(OpenBrace)) {
+                let body = if self.consume(&T!(OpenBrace)) {
                     let body = self.parse_block();
-                    self.expect_token(&T!(CloseBrace));
-                    Item::Method(ItemMethod {
-                        signature,
-                        body,
-                        inline: false,
-                    })
+                    self.expect_token(&T!(CloseBrace))?;
+                    MethodBody::Block(self.spanned(body_start, body))
                 } else if self.consume(&T!(Equals)) {
-                    let body = self.parse_inline_block();
-                    Item::Method(ItemMethod {
-                        signature,
-                        body,
-                        inline: true,
-                    })
+                    let body_start = self.start_offset();
+                    let body = self.parse_inline_block()?;
+                    MethodBody::Inline(self.spanned(body_start, body))
+                } else if self.at_end_of_declaration() {
+                    // A method without body is abstract: `method area()`
+                    MethodBody::Abstract
                 } else {
-                    self.error_in_place("Expected '{' or '=' after method signature");
-                }
+                    return self.error_in_place("Expected '{' or '=' after method signature");
+                };
+                Item::Method(ItemMethod {
+                    signature,
+                    body,
+                    doc: None,
+                })
+            }
+            Token::Keyword(kw!(@raw Abstract) | kw!(@raw Native)) => {
+                trace!("Parsing bodiless method declaration");
+                self.parse_bodiless_method(&item)?
             }
             _ => {
                 warn!("Unexpected token in item parsing: {:?}", *item);
-                self.error_in_place(format!("Unexpected token in item parsing: {:?}", *item))
+                return self.error_at(
+                    item.span,
+                    format!("Unexpected token {:?}", item.to_string()),
+                );
             }
+        };
+
+        Ok(self.spanned(start, item.with_doc(doc)))
+    }
+
+    /// Parses a property after its keywords: `property`, `const property` or `let property`
+    fn parse_property(&mut self, constant: bool) -> Result<Item> {
+        trace!("Parsing property declaration");
+        let name = self.parse_name("Expected object identifier")?;
+        let expr = self.parse_initializer()?;
+        debug!(
+            "Parsed property '{}' (initialized: {})",
+            name,
+            expr.is_some()
+        );
+        Ok(Item::Property(ItemProperty {
+            name,
+            expr,
+            constant,
+            doc: None,
+        }))
+    }
+
+    /// Parses the optional `= value` of a `let` or a property
+    fn parse_initializer(&mut self) -> Result<Option<Box<Spanned<Expr>>>> {
+        if self.consume(&T!(Equals)) {
+            return Ok(Some(Box::new(self.parse_expr()?)));
         }
+        if !self.at_end_of_declaration() {
+            return self.error_in_place("Expected '=' or the end of the declaration");
+        }
+        Ok(None)
+    }
+
+    /// Parses `abstract method foo()` and `native method foo()`, which can't have a body
+    fn parse_bodiless_method(&mut self, modifier: &Token) -> Result<Item> {
+        let body = match modifier {
+            kw!(Native) => MethodBody::Native,
+            _ => MethodBody::Abstract,
+        };
+        self.expect_token(&kw!(Method))?;
+        let signature = self.parse_method_signature()?;
+        if !self.at_end_of_declaration() {
+            return self.error_in_place(format!("{modifier} methods can't have a body"));
+        }
+        debug!("Parsed {} method '{}'", modifier, signature.ident);
+
+        Ok(Item::Method(ItemMethod {
+            signature,
+            body,
+            doc: None,
+        }))
+    }
+
+    /// Whether the declaration ends here, as a method without body or a `let` without value
+    fn at_end_of_declaration(&mut self) -> bool {
+        matches!(
+            self.peek_token(),
+            None | Some(
+                Token::Comment(_)
+                    | Token::DocComment(_)
+                    | Token::Punctuation(T!(@raw Newline) | T!(@raw CloseBrace))
+            )
+        )
     }
 
     /// Parses method signature including name and parameters
-    pub(crate) fn parse_method_signature(&mut self) -> Signature {
-        let name = self.expect_match("Expected method identifier", |t| t.into_ident());
-        self.expect_token(&T!(OpenParen));
-        let params = self.parse_identifier_list(&T!(CloseParen));
+    pub(crate) fn parse_method_signature(&mut self) -> Result<Spanned<Signature>> {
+        let start = self.start_offset();
+        let name = self.expect_match("Expected method identifier", |t| {
+            let selector = t.into_selector().or_else(|| Self::operator_selector(&t))?;
+            Some(Spanned::new(t.span, selector))
+        })?;
+        self.expect_token(&T!(OpenParen))?;
+        let params = self.parse_identifier_list(&T!(CloseParen))?;
 
         trace!(
             "Parsed method signature: {}({})",
@@ -127,120 +211,312 @@ impl Ast<'_> {
                 .join(", ")
         );
 
-        Signature {
+        let signature = Signature {
             ident: name,
             params,
-        }
+        };
+        Ok(self.spanned(start, signature))
     }
 
-    pub(crate) fn parse_params(&mut self) -> Vec<Expr> {
-        self.expect_token(&T!(OpenParen));
-        let params = self.parse_separated_list(Ast::parse_expr, &T!(Comma), &T!(CloseParen));
+    /// Operators can be defined as methods, as in `method <(otro)`. The
+    /// selector is kept as written, so `and` and `&&` are different methods
+    fn operator_selector(token: &Token) -> Option<String> {
+        Self::binary_operator(token).map(|_| token.to_string())
+    }
+
+    pub(crate) fn parse_params(&mut self) -> Result<Vec<Spanned<Expr>>> {
+        self.expect_token(&T!(OpenParen))?;
+        let params = self.parse_separated_list(Ast::parse_expr, &T!(Comma), &T!(CloseParen))?;
         trace!("Parsed {} parameters", params.len());
-        params
+        Ok(params)
+    }
+
+    /// Parses an import declaration, right after the `import` keyword:
+    /// `a.b.*`, `a.b.Clase`, `a.{B, C}`, `io.Console as Consola` or just `a`
+    pub(crate) fn parse_import(&mut self) -> Result<Item> {
+        let mut path = vec![self.parse_name("Expected module identifier")?];
+        let mut wildcard = false;
+        let mut selection = None;
+
+        while self.consume(&T!(Dot)) {
+            if self.consume(&T!(Multiply)) {
+                wildcard = true;
+                break;
+            }
+            if self.consume(&T!(OpenBrace)) {
+                let names =
+                    self.parse_separated_list(Ast::parse_import_name, &T!(Comma), &T!(CloseBrace))?;
+                selection = Some(names);
+                break;
+            }
+            path.push(self.parse_name("Expected module identifier")?);
+        }
+
+        let names = match selection {
+            Some(names) => names,
+            // A single segment names the module itself: `import trenes`
+            None if wildcard || path.len() == 1 => Vec::new(),
+            // The last segment of a plain path is the imported name: `a.b.Clase`
+            None => {
+                let name = path.remove(path.len() - 1);
+                let alias = self.parse_import_alias()?;
+                vec![self.spanned(name.span.from, ImportName { name, alias })]
+            }
+        };
+        let module = Spanned::new(
+            Span {
+                from: path[0].span.from,
+                to: path[path.len() - 1].span.to,
+            },
+            path.iter()
+                .map(|segment| segment.as_str())
+                .collect::<Vec<_>>()
+                .join("."),
+        );
+        debug!("Parsed import of '{}' with {} names", module, names.len());
+
+        Ok(Item::Import(ItemImport {
+            module,
+            wildcard,
+            names,
+        }))
+    }
+
+    /// Parses a single name inside an import selection, as in `a.{B as C}`
+    fn parse_import_name(&mut self) -> Result<Spanned<ImportName>> {
+        let start = self.start_offset();
+        let name = self.parse_name("Expected class identifier")?;
+        let alias = self.parse_import_alias()?;
+        Ok(self.spanned(start, ImportName { name, alias }))
+    }
+
+    /// `as` is only special here, anywhere else it is a name
+    fn parse_import_alias(&mut self) -> Result<Option<Spanned<String>>> {
+        if !self.consume(&Token::Ident(String::from("as"))) {
+            return Ok(None);
+        }
+        let alias = self.parse_name("Expected alias identifier")?;
+        Ok(Some(alias))
+    }
+
+    /// Parses a program, the entry point of an executable file: `program nombre { ... }`
+    pub(crate) fn parse_program(&mut self) -> Result<Item> {
+        let name = self.parse_name("Expected program identifier")?;
+        debug!("Parsing program '{}'", name);
+        let body = self.parse_braced_block()?;
+        Ok(Item::Program(ItemProgram { name, body }))
+    }
+
+    /// Parses a package grouping declarations under a qualified name: `package a.b { ... }`
+    pub(crate) fn parse_package(&mut self) -> Result<Item> {
+        let start = self.start_offset();
+        let name = self.parse_qualified_name()?;
+        let name = self.spanned(start, name);
+        debug!("Parsing package '{}'", name);
+        self.expect_token(&T!(OpenBrace))?;
+        let mut body = Vec::new();
+
+        loop {
+            self.skip_trivia();
+            if self.tokens.is_empty() || self.check(&T!(CloseBrace)) {
+                break;
+            }
+
+            let start = self.start_offset();
+            let item = self
+                .parse_package_item()
+                .unwrap_or_else(|d| self.recover_item(start, d));
+            Self::push_to_node(item, &mut body);
+        }
+
+        self.pending_doc = None;
+        self.expect_token(&T!(CloseBrace))?;
+        info!(
+            "Successfully parsed package '{}' with {} items",
+            name,
+            body.len()
+        );
+        Ok(Item::Package(ItemPackage { name, body }))
+    }
+
+    /// Packages only hold declarations, never loose expressions
+    fn parse_package_item(&mut self) -> Result<Spanned<Item>> {
+        match self.parse_statement()? {
+            Stmt::Item(item) => Ok(item),
+            Stmt::Expr(expr) => self.error_at(expr.span, "Expected a declaration inside package"),
+        }
     }
 
     /// Parses an object declaration with its body
-    pub(crate) fn parse_class(&mut self) -> Stmt {
+    pub(crate) fn parse_class(&mut self) -> Result<Item> {
         trace!("Starting class parsing");
-        let name = self.expect_match("Expected class identifier", |t| t.into_ident()); // Here we should expect the object ident.
+        let name = self.parse_name("Expected class identifier")?;
         let mut superclass = Vec::new();
         debug!("Parsing class '{}'", name);
         if self.consume(&kw!(Inherits)) {
             debug!("parsing inherits");
-            let first_name =
-                self.expect_match("Expected superclass identifier", |t| t.into_ident());
+            let first_name = self.parse_name("Expected superclass identifier")?;
             superclass.push(first_name);
 
             while self.consume(&T!(Comma)) {
                 if self.check(&T!(OpenBrace)) {
                     let (span, _) = self.advance().unwrap().split();
-                    self.error_at(span, "Expected superclass identifer, got , instead");
+                    return self.error_at(span, "Expected superclass identifer, got , instead");
                 }
-                let name = self.expect_match("Expected superclass identifier", |t| t.into_ident());
+                let name = self.parse_name("Expected superclass identifier")?;
                 superclass.push(name);
             }
         }
-        self.expect_token(&T!(OpenBrace)); // Here we should expect the `{`
+        let mixins = self.parse_mixin_list()?;
+        self.expect_token(&T!(OpenBrace))?; // Here we should expect the `{`
         self.skip_trivia();
         let body = self.parse_class_body();
-        self.expect_token(&T!(CloseBrace)); // Here we should expect the `}`
-        self.skip_trivia();
+        self.expect_token(&T!(CloseBrace))?; // Here we should expect the `}`
         info!(
             "Successfully parsed class '{}' with {} items",
             name,
             body.len()
         );
 
-        Stmt::Item(Item::Class(ItemClass {
+        Ok(Item::Class(ItemClass {
             name,
             body,
             superclass: (!superclass.is_empty()).then_some(superclass),
+            mixins,
+            doc: None,
         }))
     }
 
-    /// Parses an object declaration with its body
-    pub(crate) fn parse_object(&mut self) -> Stmt {
-        trace!("Starting object parsing");
-        let name = self.expect_match("Expected object identifier", |t| t.into_ident()); // Here we should expect the object ident.
-        debug!("Parsing object '{}'", name);
-        self.expect_token(&T!(OpenBrace)); // Here we should expect the `{`
-        self.skip_trivia();
-        let body = self.parse_object_body();
-        self.expect_token(&T!(CloseBrace)); // Here we should expect the `}`
+    /// Parses a mixin declaration with its body, which allows the same items as a class
+    pub(crate) fn parse_mixin(&mut self) -> Result<Item> {
+        trace!("Starting mixin parsing");
+        let name = self.parse_name("Expected mixin identifier")?;
+        debug!("Parsing mixin '{}'", name);
+        self.expect_token(&T!(OpenBrace))?;
         self.skip_trivia();
+        let body = self.parse_class_body();
+        self.expect_token(&T!(CloseBrace))?;
         info!(
-            "Successfully parsed object '{}' with {} items",
+            "Successfully parsed mixin '{}' with {} items",
             name,
             body.len()
         );
 
-        Stmt::Item(Item::Object(ItemObject { name, body }))
+        Ok(Item::Mixin(ItemMixin { name, body }))
     }
 
-    /// Parses the body of an object (its properties, methods, etc.)
-    pub(crate) fn parse_object_body(&mut self) -> Vec<Item> {
-        let mut body = Vec::new();
-
+    /// Parses the optional `with A, B` clause of classes and objects.
+    /// `with` is only special here, anywhere else it is a name
+    fn parse_mixin_list(&mut self) -> Result<Vec<Spanned<String>>> {
+        let mut mixins = Vec::new();
+        if !self.consume(&Token::Ident(String::from("with"))) {
+            return Ok(mixins);
+        }
         loop {
-            // Skip newlines
-            if self.consume(&T!(Newline)) {
-                continue;
-            }
-
-            // Check for end of object
-            if self.check(&T!(CloseBrace)) {
+            let mixin = self.parse_name("Expected mixin identifier")?;
+            mixins.push(mixin);
+            if !self.consume(&T!(Comma)) {
                 break;
             }
-
-            // Parse item
-            let stmt = self.parse_item();
-            Self::push_to_node(stmt, &mut body);
         }
+        debug!("Parsed {} mixins", mixins.len());
+        Ok(mixins)
+    }
 
-        body
+    /// Parses an object declaration with its body
+    pub(crate) fn parse_object(&mut self) -> Result<Item> {
+        trace!("Starting object parsing");
+        let name = self.parse_name("Expected object identifier")?;
+        debug!("Parsing object '{}'", name);
+        let object = self.parse_object_definition(name)?;
+        info!(
+            "Successfully parsed object '{}' with {} items",
+            object.name,
+            object.body.len()
+        );
+
+        Ok(Item::Object(object))
+    }
+
+    /// Parses an unnamed object expression, right after `object`: `object { method f() = 1 }`
+    pub(crate) fn parse_anonymous_object(&mut self) -> Result<Expr> {
+        trace!("Starting anonymous object parsing");
+        let ItemObject {
+            superclass,
+            mixins,
+            body,
+            ..
+        } = self.parse_object_definition(String::new().into())?;
+
+        Ok(Expr::Object(ExprObject {
+            superclass,
+            mixins,
+            body,
+        }))
     }
 
-    /// Parses the body of a class (very similar to `parse_object_body`) (its properties, methods, etc.)
-    pub(crate) fn parse_class_body(&mut self) -> Vec<Item> {
+    /// Parses everything after the object name: `inherits Ave(energia = 10) with A { ... }`
+    fn parse_object_definition(&mut self, name: Spanned<String>) -> Result<ItemObject> {
+        let superclass = if self.consume(&kw!(Inherits)) {
+            let start = self.start_offset();
+            let class = self.expect_match("Expected superclass identifier", |t| t.into_ident())?;
+            // The arguments are optional when the superclass needs none
+            let superclass = if self.check(&T!(OpenParen)) {
+                self.parse_instantiation(class)?
+            } else {
+                ExprClass {
+                    name: class,
+                    params: Vec::new(),
+                    named: Vec::new(),
+                }
+            };
+            Some(self.spanned(start, superclass))
+        } else {
+            None
+        };
+        let mixins = self.parse_mixin_list()?;
+        self.expect_token(&T!(OpenBrace))?; // Here we should expect the `{`
+        self.skip_trivia();
+        let body = self.parse_class_body();
+        self.expect_token(&T!(CloseBrace))?; // Here we should expect the `}`
+
+        Ok(ItemObject {
+            name,
+            superclass,
+            mixins,
+            body,
+            doc: None,
+        })
+    }
+
+    /// Parses the body of a class or object (its properties, methods, etc.)
+    pub(crate) fn parse_class_body(&mut self) -> Vec<Spanned<Item>> {
         let mut body = Vec::new();
 
         loop {
-            // Skip newlines
-            if self.consume(&T!(Newline)) {
-                continue;
-            }
+            // Skip newlines and comments, including trailing ones
+            self.skip_trivia();
 
             // Check for end of object
-            if self.check(&T!(CloseBrace)) {
+            if self.tokens.is_empty() || self.check(&T!(CloseBrace)) {
                 break;
             }
 
             // Parse item
-            let stmt = self.parse_class_item();
+            let start = self.start_offset();
+            let stmt = self
+                .parse_class_item()
+                .unwrap_or_else(|d| self.recover_item(start, d));
             Self::push_to_node(stmt, &mut body);
         }
 
+        self.pending_doc = None;
         body
     }
+
+    /// Records the diagnostic and returns a placeholder for the broken item
+    pub(crate) fn recover_item(&mut self, start: usize, diagnostic: Diagnostic) -> Spanned<Item> {
+        self.recover(diagnostic);
+        self.spanned(start, Item::Error)
+    }
 }
@@ -1,5 +1,7 @@
 pub mod blocks;
+pub mod closures;
 pub mod collections;
+pub mod control;
 /// Parser modules for different AST components
 ///
 /// This module organizes the parsing logic into separate, focused modules:
@@ -7,5 +9,9 @@ pub mod collections;
 /// - `items`: Handles item parsing (objects, methods, properties, etc.)
 /// - `blocks`: Handles block and statement parsing
 /// - `collections`: Handles array and set parsing
+/// - `closures`: Handles closure literals in both block and arrow forms
+/// - `control`: Handles control flow expressions (if/else, return, exceptions)
+/// - `testing`: Handles describe groups, tests and fixtures
 pub mod expressions;
 pub mod items;
+pub mod testing;
@@ -0,0 +1,120 @@
+/// Test parsing utilities
+///
+/// This module contains all testing-related parsing logic, including:
+/// - `describe` groups, which may be nested
+/// - `test` and `only test` declarations
+/// - `setup` and `after` fixtures
+use tracing::{debug, info, trace};
+use wollok_lexer::{
+    macros::{T, kw},
+    token::{Literal, Token},
+};
+
+use crate::{
+    error::Result,
+    item::{FixtureKind, Item, ItemDescribe, ItemFixture, ItemTest},
+    source::Ast,
+    span::Spanned,
+};
+
+impl Ast<'_> {
+    /// Parses a describe group with its body, right after the `describe` keyword
+    pub(crate) fn parse_describe(&mut self) -> Result<Item> {
+        trace!("Starting describe parsing");
+        let name = self.parse_test_name("Expected describe description")?;
+        debug!("Parsing describe {:?}", name);
+        self.expect_token(&T!(OpenBrace))?;
+        self.skip_trivia();
+        let body = self.parse_describe_body();
+        self.expect_token(&T!(CloseBrace))?;
+        info!(
+            "Successfully parsed describe {:?} with {} items",
+            name,
+            body.len()
+        );
+
+        Ok(Item::Describe(ItemDescribe { name, body }))
+    }
+
+    /// Parses a test, right after the `test` keyword
+    pub(crate) fn parse_test(&mut self, only: bool) -> Result<Item> {
+        let name = self.parse_test_name("Expected test description")?;
+        debug!("Parsing test {:?}", name);
+        let body = self.parse_braced_block()?;
+        Ok(Item::Test(ItemTest { name, only, body }))
+    }
+
+    /// Parses the items allowed inside a describe: declarations, methods,
+    /// fixtures, tests and nested describes
+    fn parse_describe_item(&mut self) -> Result<Spanned<Item>> {
+        self.skip_comments();
+        let start = self.start_offset();
+        let item = match self.peek_token() {
+            Some(kw!(Describe)) => {
+                self.advance();
+                self.parse_describe()?
+            }
+            Some(kw!(Test)) => {
+                self.advance();
+                self.parse_test(false)?
+            }
+            // `only`, `setup` and `after` are only special inside a describe
+            Some(Token::Ident(ref ident)) if ident == "only" => {
+                self.advance();
+                self.expect_token(&kw!(Test))?;
+                self.parse_test(true)?
+            }
+            Some(Token::Ident(ref ident)) if ident == "setup" || ident == "after" => {
+                let kind = if ident == "setup" {
+                    FixtureKind::Setup
+                } else {
+                    FixtureKind::After
+                };
+                self.advance();
+                let body = self.parse_braced_block()?;
+                Item::Fixture(ItemFixture { kind, body })
+            }
+            _ => return self.parse_item(),
+        };
+        Ok(self.spanned(start, item))
+    }
+
+    /// Parses the body of a describe (very similar to `parse_class_body`)
+    fn parse_describe_body(&mut self) -> Vec<Spanned<Item>> {
+        let mut body = Vec::new();
+
+        loop {
+            // Skip newlines and comments, including trailing ones
+            self.skip_trivia();
+
+            // Check for end of describe
+            if self.tokens.is_empty() || self.check(&T!(CloseBrace)) {
+                break;
+            }
+
+            let start = self.start_offset();
+            let item = self
+                .parse_describe_item()
+                .unwrap_or_else(|d| self.recover_item(start, d));
+            Self::push_to_node(item, &mut body);
+        }
+
+        self.pending_doc = None;
+        body
+    }
+
+    /// Whether the next tokens are `only test`. `only` is just an identifier
+    /// anywhere else, as in `only.size()`.
+    pub(crate) fn at_only_test(&self) -> bool {
+        matches!(self.tokens.front(), Some(t) if matches!(t.token, Token::Ident(ref ident) if ident == "only"))
+            && self.tokens.get(1).is_some_and(|t| t.token == kw!(Test))
+    }
+
+    /// Tests and describes are named by a string literal
+    fn parse_test_name(&mut self, msg: &str) -> Result<Spanned<String>> {
+        self.expect_match(msg, |t| match t.token {
+            Token::Literal(Literal::String(name)) => Some(Spanned::new(t.span, name.value)),
+            _ => None,
+        })
+    }
+}
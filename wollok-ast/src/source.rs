@@ -1,17 +1,26 @@
+use tracing::warn;
 use wollok_lexer::{
     lexer::TokenStream,
-    macros::T,
-    token::{Span, SpannedToken, Token},
+    macros::{T, kw},
+    token::{Keyword, Span, SpannedToken, Token},
 };
 
-use ariadne::{Color, Label, Report, ReportBuilder, ReportKind, Source};
 use std::{collections::VecDeque, fmt};
 
+use crate::{
+    error::{Diagnostic, Result},
+    item::Ident,
+    span::Spanned,
+};
+
 #[derive(Debug, Clone)]
 pub struct Ast<'a> {
     pub base: &'a str,
     pub last_offset: usize,
     pub tokens: VecDeque<SpannedToken>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Doc comment skipped as trivia, waiting for the item it documents
+    pub pending_doc: Option<String>,
 }
 
 #[derive(Debug)]
@@ -22,12 +31,20 @@ pub struct PeekedToken<'a, 's> {
 }
 
 impl<'i> Ast<'i> {
+    /// Lexes the whole input in recovering mode. Lexer errors are kept as
+    /// diagnostics and only the successfully lexed tokens are parsed.
     #[must_use]
     pub fn new(base: &'i str, tokens: TokenStream<'i>) -> Self {
+        let (tokens, errors) = tokens.collect_recovering();
         Ast {
             base,
             last_offset: 0,
-            tokens: tokens.collect_deque().unwrap_or_else(|_| VecDeque::new()),
+            tokens: tokens
+                .into_iter()
+                .filter(|t| !matches!(t.token, Token::Error(_)))
+                .collect(),
+            diagnostics: errors.into_iter().map(Diagnostic::from).collect(),
+            pending_doc: None,
         }
     }
 
@@ -89,118 +106,188 @@ impl<'i> Ast<'i> {
         })
     }
 
-    pub fn peek_expect<'a>(&'a mut self) -> PeekedToken<'i, 'a> {
+    /// # Errors
+    /// Returns a diagnostic if there are no more tokens.
+    pub fn peek_expect<'a>(&'a mut self) -> Result<PeekedToken<'i, 'a>> {
         // The implementation cannot be done with `peek` call
         // because of borrow checker :|
         let Some(token) = self.tokens.pop_front() else {
-            self.error_in_place("Unexpected EOF");
+            return self.error_in_place("Unexpected EOF");
         };
 
         let last_offset = self.last_offset;
         self.last_offset = token.span.to;
-        PeekedToken {
+        Ok(PeekedToken {
             token,
             last_offset,
             src: self,
-        }
+        })
     }
 
-    pub fn expect(&mut self) -> SpannedToken {
-        self.tokens
-            .pop_front()
-            .inspect(|t| self.last_offset = t.span.to)
-            .unwrap_or_else(|| self.error_in_place("Unexpected EOF"))
+    /// # Errors
+    /// Returns a diagnostic if there are no more tokens.
+    pub fn expect(&mut self) -> Result<SpannedToken> {
+        match self.advance() {
+            Some(token) => Ok(token),
+            None => self.error_in_place("Unexpected EOF"),
+        }
     }
 
-    pub fn expect_msg(&mut self, msg: impl fmt::Display) -> SpannedToken {
-        self.tokens
-            .pop_front()
-            .inspect(|t| self.last_offset = t.span.to)
-            .unwrap_or_else(|| self.error_in_place(format!("Unexpected EOF. {msg}")))
+    /// # Errors
+    /// Returns a diagnostic including `msg` if there are no more tokens.
+    pub fn expect_msg(&mut self, msg: impl fmt::Display) -> Result<SpannedToken> {
+        match self.advance() {
+            Some(token) => Ok(token),
+            None => self.error_in_place(format!("Unexpected EOF. {msg}")),
+        }
     }
 
+    /// # Errors
+    /// Returns a diagnostic if there are no more tokens or the predicate rejects the next one.
     pub fn expect_match<T>(
         &mut self,
-        msg: impl fmt::Display + Clone,
-        predicate: impl Fn(SpannedToken) -> Option<T>,
-    ) -> T {
-        let expected_err = format!("Expected {msg}");
-        let first = self.expect_msg(&expected_err);
-
+        msg: impl fmt::Display,
+        predicate: impl FnOnce(SpannedToken) -> Option<T>,
+    ) -> Result<T> {
+        let first = self.expect_msg(&msg)?;
         let span = first.span;
-        let unexpected_err = format!("Unexpected token: {first:?}");
+        let unexpected_err = format!("Unexpected token {:?}", first.token.to_string());
 
         if let Some(t) = predicate(first) {
-            self.last_offset = span.to;
-            t
+            Ok(t)
         } else {
-            self.error_build(
-                span,
-                |b| {
-                    b.with_message(unexpected_err).with_label(
-                        Label::new(span)
-                            .with_color(Color::BrightRed)
-                            .with_message(expected_err),
-                    )
-                },
-                msg.clone(),
-            );
+            Err(Diagnostic::new(span, unexpected_err).with_label(span, msg))
         }
     }
 
-    pub fn expect_token(&mut self, token: &Token) -> SpannedToken {
-        let mut checkpoint = self.clone();
-        let fetched = self.peek_expect().token;
-        checkpoint.expect_match(format!("{token:#?} but got {fetched:#?}"), |t| {
+    /// # Errors
+    /// Returns a diagnostic if the next token is not `token`.
+    pub fn expect_token(&mut self, token: &Token) -> Result<SpannedToken> {
+        self.expect_match(format!("Expected {:?}", token.to_string()), |t| {
             (*t == *token).then_some(t)
         })
     }
 
-    pub fn error_in_place(&self, msg: impl fmt::Display + Clone) -> ! {
-        let span = Span::char(self.last_offset - 1);
-        self.error_build(
-            span,
-            |b| {
-                b.with_message(msg.clone()).with_label(
-                    Label::new(span)
-                        .with_message(msg.clone())
-                        .with_color(ariadne::Color::BrightRed),
-                )
-            },
-            msg.clone(),
-        )
+    /// Builds a diagnostic pointing at the last consumed token
+    ///
+    /// # Errors
+    /// Always.
+    pub fn error_in_place<T>(&self, msg: impl fmt::Display) -> Result<T> {
+        let span = Span::char(self.last_offset.saturating_sub(1));
+        self.error_at(span, msg)
     }
 
-    pub fn error_at(&self, span: Span, msg: impl fmt::Display + Clone) -> ! {
-        let cloned_msg = msg.clone();
-        self.error_build(
-            span,
-            |b| {
-                b.with_message(&msg).with_label(
-                    Label::new(span)
-                        .with_message(msg)
-                        .with_color(ariadne::Color::BrightRed),
-                )
-            },
-            cloned_msg,
+    /// Builds a diagnostic pointing at `span`
+    ///
+    /// # Errors
+    /// Always.
+    pub fn error_at<T>(&self, span: Span, msg: impl fmt::Display) -> Result<T> {
+        Err(Diagnostic::new(span, &msg).with_label(span, msg))
+    }
+
+    // ======== Spans ========
+
+    /// Offset where the next token starts (or the end of the input)
+    pub(crate) fn start_offset(&self) -> usize {
+        self.tokens
+            .front()
+            .map_or(self.last_offset, |token| token.span.from)
+    }
+
+    /// Wraps `node` with the span going from `start` to the end of the last consumed token
+    pub(crate) fn spanned<T>(&self, start: usize, node: T) -> Spanned<T> {
+        let span = Span {
+            from: start,
+            to: self.last_offset.max(start),
+        };
+        Spanned::new(span, node)
+    }
+
+    // ======== Error Recovery ========
+
+    /// Records the diagnostic and skips tokens until a plausible statement boundary
+    pub(crate) fn recover(&mut self, diagnostic: Diagnostic) {
+        warn!("Recovering from syntax error: {}", diagnostic);
+        self.diagnostics.push(diagnostic);
+        self.synchronize();
+    }
+
+    /// Skips tokens until the end of the current line, the `}` closing the
+    /// enclosing block (not consumed) or the start of a new declaration.
+    /// Nested delimiters are skipped as a whole.
+    pub(crate) fn synchronize(&mut self) {
+        let mut depth = 0usize;
+
+        while let Some(token) = self.peek() {
+            let stop = match **token {
+                Token::Punctuation(
+                    T!(@raw OpenBrace) | T!(@raw OpenParen) | T!(@raw OpenSquareBracket),
+                ) => {
+                    depth += 1;
+                    false
+                }
+                Token::Punctuation(
+                    T!(@raw CloseBrace) | T!(@raw CloseParen) | T!(@raw CloseSquareBracket),
+                ) if depth > 0 => {
+                    depth -= 1;
+                    false
+                }
+                T!(CloseBrace) => {
+                    token.recover();
+                    return;
+                }
+                T!(Newline) => depth == 0,
+                Token::Keyword(ref keyword) if depth == 0 && Self::is_declaration(keyword) => {
+                    token.recover();
+                    return;
+                }
+                _ => false,
+            };
+
+            _ = token.accept();
+            if stop {
+                return;
+            }
+        }
+    }
+
+    /// Whether the keyword starts a declaration, used as a synchronization point
+    pub(crate) fn is_declaration(keyword: &Keyword) -> bool {
+        matches!(
+            keyword,
+            kw!(@raw Object)
+                | kw!(@raw Class)
+                | kw!(@raw Method)
+                | kw!(@raw Property)
+                | kw!(@raw Const)
+                | kw!(@raw Let)
+                | kw!(@raw Override)
+                | kw!(@raw Fallible)
+                | kw!(@raw Import)
+                | kw!(@raw Describe)
+                | kw!(@raw Test)
+                | kw!(@raw Program)
+                | kw!(@raw Package)
+                | kw!(@raw Mixin)
+                | kw!(@raw Abstract)
+                | kw!(@raw Native)
         )
     }
 
-    /// # Panics
-    /// Only when it's called
-    pub fn error_build(
-        &self,
-        span: Span,
-        fun: impl FnOnce(ReportBuilder<Span>) -> ReportBuilder<Span>,
-        msg: impl fmt::Display,
-    ) -> ! {
-        if !cfg!(test) {
-            _ = fun(Report::build(ReportKind::Error, span))
-                .finish()
-                .eprint(Source::from(self.base));
+    /// Whether the next token starts a declaration
+    pub(crate) fn at_declaration(&mut self) -> bool {
+        match self.peek_token() {
+            Some(kw!(Object)) => self.at_named_object(),
+            Some(Token::Keyword(ref keyword)) => Self::is_declaration(keyword),
+            _ => self.at_only_test(),
         }
+    }
 
-        panic!("{msg}");
+    /// Whether the next tokens are `object` and a name. Without the name it is
+    /// an anonymous object, an expression as in `object { ... }.f()`
+    pub(crate) fn at_named_object(&self) -> bool {
+        matches!(self.tokens.front(), Some(t) if t.token == kw!(Object))
+            && matches!(self.tokens.get(1), Some(t) if matches!(t.token, Token::Ident(_)))
     }
 
     // ======== Helper Methods - Phase 1 ========
@@ -223,53 +310,83 @@ impl<'i> Ast<'i> {
     }
 
     /// Parse a comma-separated list with generic element parser
+    ///
+    /// # Errors
+    /// Returns the first diagnostic of an element or if the terminator is missing.
     pub fn parse_separated_list<T>(
         &mut self,
-        element_parser: impl Fn(&mut Self) -> T,
+        element_parser: impl Fn(&mut Self) -> Result<T>,
         separator: &Token,
         terminator: &Token,
-    ) -> Vec<T> {
+    ) -> Result<Vec<T>> {
         let mut elements = Vec::new();
 
         // Check for empty list
         if self.check(terminator) {
             self.consume(terminator);
-            return elements;
+            return Ok(elements);
         }
 
         // Parse first element
-        elements.push(element_parser(self));
+        elements.push(element_parser(self)?);
 
         // Parse remaining elements
         while self.consume(separator) {
-            // Check for trailing separator
-            if self.check(terminator) {
-                self.consume(terminator);
-                break;
+            // Check for trailing separator, even on its own line
+            self.skip_trivia();
+            if self.consume(terminator) {
+                return Ok(elements);
             }
-            elements.push(element_parser(self));
+            elements.push(element_parser(self)?);
         }
 
         // Consume terminator
         if !self.consume(terminator) {
-            self.error_in_place(format!("Expected '{terminator}'"));
+            return self.error_in_place(format!("Expected '{terminator}'"));
         }
 
-        elements
+        Ok(elements)
+    }
+
+    /// Parses the name of a declaration, keeping its location
+    ///
+    /// # Errors
+    /// Returns a diagnostic labeled with `msg` if the next token is not an identifier.
+    pub fn parse_name(&mut self, msg: &str) -> Result<Spanned<String>> {
+        self.expect_match(msg, |t| Some(Spanned::new(t.span, t.into_ident()?)))
     }
 
     /// Parse a comma-separated list of identifiers (for method parameters)
-    pub fn parse_identifier_list(&mut self, terminator: &Token) -> Vec<crate::item::Ident> {
+    ///
+    /// # Errors
+    /// Returns a diagnostic if an element is not an identifier or the terminator is missing.
+    pub fn parse_identifier_list(&mut self, terminator: &Token) -> Result<Vec<Spanned<Ident>>> {
         self.parse_separated_list(
             |parser| {
-                let name = parser.expect_match("Expected identifier", |t| t.into_ident());
-                crate::item::Ident { name }
+                parser.expect_match("Expected identifier", |t| {
+                    let name = t.into_ident()?;
+                    Some(Spanned::new(t.span, Ident { name }))
+                })
             },
             &T!(Comma),
             terminator,
         )
     }
 
+    /// Parse a dot separated name such as `MiError` or `wollok.lang.Exception`
+    ///
+    /// # Errors
+    /// Returns a diagnostic if a segment is not an identifier.
+    pub fn parse_qualified_name(&mut self) -> Result<String> {
+        let mut name = self.expect_match("Expected identifier", |t| t.into_ident())?;
+        while self.consume(&T!(Dot)) {
+            let segment = self.expect_match("Expected identifier", |t| t.into_ident())?;
+            name.push('.');
+            name.push_str(&segment);
+        }
+        Ok(name)
+    }
+
     /// Unified whitespace and comment handling
     pub fn skip_trivia(&mut self) {
         while let Some(token) = self.peek_token() {
@@ -277,10 +394,29 @@ impl<'i> Ast<'i> {
                 Token::Comment(_) | T!(Newline) => {
                     let _ = self.advance();
                 }
+                Token::DocComment(doc) => {
+                    let _ = self.advance();
+                    self.pending_doc = Some(doc);
+                }
                 _ => break,
             }
         }
     }
+
+    /// Checks whether the first token after any newlines and comments is
+    /// `expected`, without consuming anything
+    #[must_use]
+    pub fn check_past_trivia(&self, expected: &Token) -> bool {
+        self.tokens
+            .iter()
+            .find(|t| {
+                !matches!(
+                    t.token,
+                    Token::Comment(_) | Token::DocComment(_) | T!(Newline)
+                )
+            })
+            .is_some_and(|t| t.token == *expected)
+    }
 }
 
 impl PeekedToken<'_, '_> {
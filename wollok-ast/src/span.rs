@@ -0,0 +1,66 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use wollok_lexer::token::Span;
+
+/// An AST node together with its location in the source code.
+///
+/// As with `SpannedToken`, equality only compares the nodes, so trees can be
+/// compared structurally regardless of where they were parsed from.
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    pub span: Span,
+    pub node: T,
+}
+
+impl<T> Spanned<T> {
+    #[must_use]
+    pub const fn new(span: Span, node: T) -> Self {
+        Self { span, node }
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.node
+    }
+
+    #[must_use]
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned::new(self.span, f(self.node))
+    }
+}
+
+/// Builds a node without location, mostly useful to write trees by hand
+impl<T> From<T> for Spanned<T> {
+    fn from(node: T) -> Self {
+        Self::new(Span::ZERO, node)
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl<T> DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.node
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.node)
+    }
+}
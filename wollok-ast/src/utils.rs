@@ -1,10 +1,10 @@
 #[macro_export]
 macro_rules! stmt {
     (@item $body:expr) => {
-        $crate::ast::Stmt::Item($body)
+        $crate::ast::Stmt::Item($body.into())
     };
     (@expr $body:expr) => {
-        $crate::ast::Stmt::Expr($body)
+        $crate::ast::Stmt::Expr($body.into())
     };
 }
 
@@ -24,30 +24,49 @@ macro_rules! items {
     };
     (@const $name:expr, $value:expr) => {
         $crate::item::Item::Const($crate::item::ItemConst {
-            name: $name.to_owned(),
-            expr: $value,
+            name: $name.to_owned().into(),
+            expr: Box::new($value.into()),
         })
     };
     (@let $name:expr, $value:expr) => {
         $crate::item::Item::Let($crate::item::ItemLet {
-            name: $name.to_owned(),
-            expr: $value,
+            name: $name.to_owned().into(),
+            expr: Some(Box::new($value.into())),
+        })
+    };
+    (@let $name:expr) => {
+        $crate::item::Item::Let($crate::item::ItemLet {
+            name: $name.to_owned().into(),
+            expr: None,
         })
     };
     (@object $name:expr, [$($body:expr),*]) => {
         $crate::item::Item::Object($crate::item::ItemObject {
-            name: $name.to_owned(),
-            body: vec![$($body),*],
+            name: $name.to_owned().into(),
+            superclass: None,
+            mixins: Vec::new(),
+            body: vec![$($body.into()),*],
+            doc: None,
         })
     };
     (@method $name:expr, $params:expr, $body:expr, $inline:expr) => {
         $crate::item::Item::Method($crate::item::ItemMethod {
             signature: $crate::item::Signature {
-                ident: $name.to_owned(),
-                params: $params,
+                ident: $name.to_owned().into(),
+                params: {
+                    let params: Vec<$crate::item::Ident> = $params;
+                    params.into_iter().map(Into::into).collect()
+                },
+              }.into(),
+              body: {
+                  let body = $crate::expr::Block { stmts: $body }.into();
+                  if $inline {
+                      $crate::item::MethodBody::Inline(body)
+                  } else {
+                      $crate::item::MethodBody::Block(body)
+                  }
               },
-              body: $crate::expr::Block { stmts: $body },
-              inline: $inline,
+              doc: None,
         })
     };
 }
@@ -56,12 +75,12 @@ macro_rules! items {
 macro_rules! exprs {
   (@array [$($elements:expr),*]) => {
     $crate::expr::Expr::Array($crate::expr::ExprArray {
-      elements: vec![$($elements),*],
+      elements: vec![$($elements.into()),*],
     })
   };
   (@set [$($elements:expr),*]) => {
     $crate::expr::Expr::Set($crate::expr::ExprSet {
-      elements: vec![$($elements),*],
+      elements: vec![$($elements.into()),*],
     })
   };
   (@lit $value:expr) => {
@@ -70,22 +89,124 @@ macro_rules! exprs {
   (@self) => {
     $crate::expr::Expr::Self_
   };
+  (@super [$($args:expr),*]) => {
+    $crate::expr::Expr::Super($crate::expr::ExprSuper {
+      args: vec![$($args.into()),*],
+    })
+  };
   (@class $name:expr, $params:expr) => {
     $crate::expr::Expr::Class($crate::expr::ExprClass {
       name: $name.to_owned(),
-      params: $params,
+      params: {
+        let params: Vec<$crate::expr::Expr> = $params;
+        params.into_iter().map(Into::into).collect()
+      },
+      named: Vec::new(),
+    })
+  };
+  (@new $name:expr, [$($arg:expr => $value:expr),*]) => {
+    $crate::expr::Expr::Class($crate::expr::ExprClass {
+      name: $name.to_owned(),
+      params: Vec::new(),
+      named: vec![$($crate::expr::NamedArg {
+        name: $arg.to_owned().into(),
+        value: $value.into(),
+      }.into()),*],
     })
   };
   (@assign $left:expr, $right:expr) => {
     $crate::expr::Expr::Assign($crate::expr::ExprAssign {
-      left: Box::new($left),
-      right: Box::new($right),
+      left: Box::new($left.into()),
+      right: Box::new($right.into()),
+    })
+  };
+  (@binary $selector:literal, $left:expr, $right:expr) => {
+    $crate::expr::Expr::MethodCall($crate::expr::ExprMethodCall::binary(
+      $left.into(),
+      $selector.to_owned().into(),
+      $right.into(),
+    ))
+  };
+  (@binary $op:ident, $left:expr, $right:expr) => {
+    $crate::expr::Expr::MethodCall($crate::expr::ExprMethodCall::binary(
+      $left.into(),
+      wollok_common::ast::BinaryOp::$op.selector().to_owned().into(),
+      $right.into(),
+    ))
+  };
+  (@unary $op:ident, $expr:expr) => {
+    $crate::expr::Expr::Unary($crate::expr::ExprUnary {
+      op: wollok_common::ast::UnaryOp::$op,
+      expr: Box::new($expr.into()),
+    })
+  };
+  (@block [$($stmts:expr),*]) => {
+    $crate::expr::Expr::Block($crate::expr::ExprBlock {
+      block: $crate::expr::Block { stmts: vec![$($stmts),*] },
+    })
+  };
+  (@if $cond:expr, [$($then:expr),*]) => {
+    $crate::expr::Expr::If($crate::expr::ExprIf {
+      condition: Box::new($cond.into()),
+      then: $crate::expr::Block { stmts: vec![$($then),*] }.into(),
+      otherwise: None,
     })
   };
+  (@if $cond:expr, [$($then:expr),*], $otherwise:expr) => {
+    $crate::expr::Expr::If($crate::expr::ExprIf {
+      condition: Box::new($cond.into()),
+      then: $crate::expr::Block { stmts: vec![$($then),*] }.into(),
+      otherwise: Some(Box::new($otherwise.into())),
+    })
+  };
+  (@closure [$($params:expr),*], [$($stmts:expr),*]) => {
+    $crate::expr::Expr::Closure($crate::expr::ExprClosure {
+      params: vec![$($params.into()),*],
+      body: $crate::expr::Block { stmts: vec![$($stmts),*] }.into(),
+    })
+  };
+  (@send $name:expr, $receiver:expr, [$($args:expr),*]) => {
+    $crate::expr::Expr::MethodCall($crate::expr::ExprMethodCall {
+      receiver: Box::new($receiver.into()),
+      name: $name.to_owned().into(),
+      args: vec![$($args.into()),*],
+    })
+  };
+  (@return) => {
+    $crate::expr::Expr::Return($crate::expr::ExprReturn { value: None })
+  };
+  (@return $value:expr) => {
+    $crate::expr::Expr::Return($crate::expr::ExprReturn {
+      value: Some(Box::new($value.into())),
+    })
+  };
+  (@throw $expr:expr) => {
+    $crate::expr::Expr::Throw($crate::expr::ExprThrow {
+      expr: Box::new($expr.into()),
+    })
+  };
+  (@try $expr:expr) => {
+    $crate::expr::Expr::Try($crate::expr::ExprTry {
+      expr: Box::new($expr.into()),
+    })
+  };
+  (@propagate $expr:expr) => {
+    $crate::expr::Expr::Propagate($crate::expr::ExprPropagate {
+      expr: Box::new($expr.into()),
+    })
+  };
+  (@assert $expr:expr) => {
+    $crate::expr::Expr::Assert($crate::expr::ExprAssert {
+      expr: Box::new($expr.into()),
+    })
+  };
+  (@field $name:expr) => {
+    $crate::exprs!(@field $name, $crate::expr::Expr::Self_)
+  };
   (@field $name:expr, $base:expr) => {
     $crate::expr::Expr::Field($crate::expr::ExprField {
-      name: $name.to_owned(),
-      base: Box::new($base),
+      name: $name.to_owned().into(),
+      base: Box::new($base.into()),
     })
   };
 }
@@ -1,16 +1,24 @@
 #[must_use]
 fn parse(input: &'_ str) -> Scope {
-    Scope::from_tokens(input, TokenStream::new(input))
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    scope
 }
 
-use wollok_lexer::lexer::TokenStream;
+use wollok_lexer::{lexer::TokenStream, token::Span};
 
 use wollok_ast::{
     ast::{Scope, Stmt},
-    expr::{Expr, ExprArray, ExprClass, ExprLit, ExprSet},
+    expr::{Block, Expr, ExprArray, ExprClass, ExprLit, ExprObject, ExprSet, NamedArg},
     exprs, ident,
-    item::{Item, ItemConst},
-    items, stmt,
+    item::{
+        FixtureKind, ImportName, Item, ItemClass, ItemConst, ItemDescribe, ItemFixture, ItemImport,
+        ItemMethod, ItemMixin, ItemObject, ItemPackage, ItemPrefixedMethod, ItemProgram,
+        ItemProperty, ItemTest, MethodBody, Prefix, Signature,
+    },
+    items,
+    span::Spanned,
+    stmt,
 };
 
 #[test]
@@ -19,9 +27,9 @@ fn test_array_parse() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![stmt!(@item items!(@const "items", Box::new(
+        vec![stmt!(@item items!(@const "items",
             exprs!(@array [exprs!(@lit 1), exprs!(@lit 2), exprs!(@lit 3)])
-        )))]
+        ))]
     );
 }
 
@@ -31,7 +39,7 @@ fn test_literal_parse() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![stmt!(@item items!(@const "value", Box::new(exprs!(@lit 42))))]
+        vec![stmt!(@item items!(@const "value", exprs!(@lit 42)))]
     );
 }
 
@@ -47,7 +55,7 @@ fn test_object_with_assignment_in_method() {
     assert_eq!(
         *scope,
         vec![stmt!(@item items!(@object "foo", [
-          items!(@let "a", Box::new(exprs!(@lit 1))),
+          items!(@let "a", exprs!(@lit 1)),
           items!(@method "do", vec![], vec![
             stmt!(@expr exprs!(@assign exprs!(@field "a", exprs!(@self)), exprs!(@lit 2)))
           ], false)
@@ -61,9 +69,9 @@ fn test_set_parse() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![stmt!(@item items!(@const "numbers", Box::new(
+        vec![stmt!(@item items!(@const "numbers",
           exprs!(@set [exprs!(@lit 1), exprs!(@lit 2), exprs!(@lit 3)])
-        )))]
+        ))]
     );
 }
 
@@ -73,12 +81,12 @@ fn test_nested_collections() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![stmt!(@item items!(@const "nested", Box::new(
+        vec![stmt!(@item items!(@const "nested",
           exprs!(@array [
             exprs!(@array [exprs!(@lit 1), exprs!(@lit 2)]),
             exprs!(@array [exprs!(@lit 3), exprs!(@lit 4)])
           ])
-        )))]
+        ))]
     );
 }
 
@@ -109,13 +117,20 @@ fn test_class_expr() {
 
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "bar".into(),
-            expr: Box::new(Expr::Class(ExprClass {
-                name: "Foo".into(),
-                params: vec![]
-            }))
-        }))]
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "bar".to_string().into(),
+                expr: Box::new(
+                    Expr::Class(ExprClass {
+                        name: "Foo".into(),
+                        params: vec![],
+                        named: vec![],
+                    })
+                    .into()
+                )
+            })
+            .into()
+        )]
     );
 }
 
@@ -126,16 +141,23 @@ fn test_class_with_args() {
 
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "bar".into(),
-            expr: Box::new(Expr::Class(ExprClass {
-                name: "Foo".into(),
-                params: vec![
-                    Expr::Lit(ExprLit { value: 1.into() }),
-                    Expr::Lit(ExprLit { value: 2.into() }),
-                ]
-            }))
-        }))]
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "bar".to_string().into(),
+                expr: Box::new(
+                    Expr::Class(ExprClass {
+                        name: "Foo".into(),
+                        params: vec![
+                            Expr::Lit(ExprLit { value: 1.into() }).into(),
+                            Expr::Lit(ExprLit { value: 2.into() }).into(),
+                        ],
+                        named: vec![],
+                    })
+                    .into()
+                )
+            })
+            .into()
+        )]
     );
 }
 
@@ -146,19 +168,28 @@ fn test_complex_args() {
 
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "bar".into(),
-            expr: Box::new(Expr::Class(ExprClass {
-                name: "Foo".into(),
-                params: vec![
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "bar".to_string().into(),
+                expr: Box::new(
                     Expr::Class(ExprClass {
-                        name: "Bar".into(),
-                        params: vec![]
-                    }),
-                    Expr::Lit(ExprLit { value: 2.into() }),
-                ]
-            }))
-        }))]
+                        name: "Foo".into(),
+                        params: vec![
+                            Expr::Class(ExprClass {
+                                name: "Bar".into(),
+                                params: vec![],
+                                named: vec![],
+                            })
+                            .into(),
+                            Expr::Lit(ExprLit { value: 2.into() }).into(),
+                        ],
+                        named: vec![],
+                    })
+                    .into()
+                )
+            })
+            .into()
+        )]
     );
 }
 
@@ -168,10 +199,13 @@ fn test_empty_array() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "empty".into(),
-            expr: Box::new(Expr::Array(ExprArray { elements: vec![] })),
-        }))]
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "empty".to_string().into(),
+                expr: Box::new(Expr::Array(ExprArray { elements: vec![] }).into()),
+            })
+            .into()
+        )]
     );
 }
 
@@ -181,10 +215,13 @@ fn test_empty_set() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "empty".into(),
-            expr: Box::new(Expr::Set(ExprSet { elements: vec![] })),
-        }))]
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "empty".to_string().into(),
+                expr: Box::new(Expr::Set(ExprSet { elements: vec![] }).into()),
+            })
+            .into()
+        )]
     );
 }
 
@@ -194,18 +231,25 @@ fn test_mixed_type_array() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "mixed".into(),
-            expr: Box::new(Expr::Array(ExprArray {
-                elements: vec![
-                    Expr::Lit(ExprLit { value: 1.into() }),
-                    Expr::Lit(ExprLit {
-                        value: "hello".into()
-                    }),
-                    Expr::Lit(ExprLit { value: true.into() }),
-                ],
-            })),
-        }))]
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "mixed".to_string().into(),
+                expr: Box::new(
+                    Expr::Array(ExprArray {
+                        elements: vec![
+                            Expr::Lit(ExprLit { value: 1.into() }).into(),
+                            Expr::Lit(ExprLit {
+                                value: "hello".into()
+                            })
+                            .into(),
+                            Expr::Lit(ExprLit { value: true.into() }).into(),
+                        ],
+                    })
+                    .into()
+                ),
+            })
+            .into()
+        )]
     );
 }
 
@@ -215,20 +259,28 @@ fn test_mixed_type_set() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "mixed".into(),
-            expr: Box::new(Expr::Set(ExprSet {
-                elements: vec![
-                    Expr::Lit(ExprLit { value: 1.into() }),
-                    Expr::Lit(ExprLit {
-                        value: "hello".into()
-                    }),
-                    Expr::Lit(ExprLit {
-                        value: false.into()
-                    }),
-                ],
-            })),
-        }))]
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "mixed".to_string().into(),
+                expr: Box::new(
+                    Expr::Set(ExprSet {
+                        elements: vec![
+                            Expr::Lit(ExprLit { value: 1.into() }).into(),
+                            Expr::Lit(ExprLit {
+                                value: "hello".into()
+                            })
+                            .into(),
+                            Expr::Lit(ExprLit {
+                                value: false.into()
+                            })
+                            .into(),
+                        ],
+                    })
+                    .into()
+                ),
+            })
+            .into()
+        )]
     );
 }
 
@@ -238,28 +290,37 @@ fn test_nested_mixed_collections() {
     let scope = parse(input);
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "complex".into(),
-            expr: Box::new(Expr::Array(ExprArray {
-                elements: vec![
-                    Expr::Set(ExprSet {
-                        elements: vec![
-                            Expr::Lit(ExprLit { value: 1.into() }),
-                            Expr::Lit(ExprLit { value: 2.into() }),
-                        ],
-                    }),
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "complex".to_string().into(),
+                expr: Box::new(
                     Expr::Array(ExprArray {
                         elements: vec![
-                            Expr::Lit(ExprLit { value: 3.into() }),
-                            Expr::Lit(ExprLit { value: 4.into() }),
+                            Expr::Set(ExprSet {
+                                elements: vec![
+                                    Expr::Lit(ExprLit { value: 1.into() }).into(),
+                                    Expr::Lit(ExprLit { value: 2.into() }).into(),
+                                ],
+                            })
+                            .into(),
+                            Expr::Array(ExprArray {
+                                elements: vec![
+                                    Expr::Lit(ExprLit { value: 3.into() }).into(),
+                                    Expr::Lit(ExprLit { value: 4.into() }).into(),
+                                ],
+                            })
+                            .into(),
+                            Expr::Lit(ExprLit {
+                                value: "text".into()
+                            })
+                            .into(),
                         ],
-                    }),
-                    Expr::Lit(ExprLit {
-                        value: "text".into()
-                    }),
-                ],
-            })),
-        }))]
+                    })
+                    .into()
+                ),
+            })
+            .into()
+        )]
     );
 }
 
@@ -270,10 +331,22 @@ fn test_method_no_params() {
         }";
     let scope = parse(input);
 
-    if let [Stmt::Item(Item::Object(obj))] = scope.as_slice() {
-        assert_eq!(obj.name, "counter");
-        if let [Item::Method(method)] = obj.body.as_slice() {
-            assert_eq!(method.signature.ident, "increment");
+    if let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    {
+        assert_eq!(*obj.name, "counter");
+        if let [
+            Spanned {
+                node: Item::Method(method),
+                ..
+            },
+        ] = obj.body.as_slice()
+        {
+            assert_eq!(*method.signature.ident, "increment");
             assert_eq!(method.signature.params.len(), 0);
         } else {
             panic!("Expected one method in object body");
@@ -290,10 +363,22 @@ fn test_method_single_param() {
         }";
     let scope = parse(input);
 
-    if let [Stmt::Item(Item::Object(obj))] = scope.as_slice() {
-        assert_eq!(obj.name, "calculator");
-        if let [Item::Method(method)] = obj.body.as_slice() {
-            assert_eq!(method.signature.ident, "double");
+    if let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    {
+        assert_eq!(*obj.name, "calculator");
+        if let [
+            Spanned {
+                node: Item::Method(method),
+                ..
+            },
+        ] = obj.body.as_slice()
+        {
+            assert_eq!(*method.signature.ident, "double");
             assert_eq!(method.signature.params.len(), 1);
             assert_eq!(method.signature.params[0].name, "x");
         } else {
@@ -311,10 +396,22 @@ fn test_method_many_params() {
         }";
     let scope = parse(input);
 
-    if let [Stmt::Item(Item::Object(obj))] = scope.as_slice() {
-        assert_eq!(obj.name, "calculator");
-        if let [Item::Method(method)] = obj.body.as_slice() {
-            assert_eq!(method.signature.ident, "calculate");
+    if let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    {
+        assert_eq!(*obj.name, "calculator");
+        if let [
+            Spanned {
+                node: Item::Method(method),
+                ..
+            },
+        ] = obj.body.as_slice()
+        {
+            assert_eq!(*method.signature.ident, "calculate");
             assert_eq!(method.signature.params.len(), 5);
             assert_eq!(method.signature.params[0].name, "a");
             assert_eq!(method.signature.params[1].name, "b");
@@ -337,20 +434,26 @@ fn test_object_with_multiple_items() {
         }";
     let scope = parse(input);
 
-    if let [Stmt::Item(Item::Object(obj))] = scope.as_slice() {
-        assert_eq!(obj.name, "complex");
+    if let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    {
+        assert_eq!(*obj.name, "complex");
         assert_eq!(obj.body.len(), 2);
 
         // Check const
-        if let Item::Const(const_item) = &obj.body[0] {
-            assert_eq!(const_item.name, "value");
+        if let Item::Const(const_item) = &obj.body[0].node {
+            assert_eq!(*const_item.name, "value");
         } else {
             panic!("Expected const item");
         }
 
         // Check let
-        if let Item::Let(let_item) = &obj.body[1] {
-            assert_eq!(let_item.name, "mutable");
+        if let Item::Let(let_item) = &obj.body[1].node {
+            assert_eq!(*let_item.name, "mutable");
         } else {
             panic!("Expected let item");
         }
@@ -366,25 +469,36 @@ fn test_nested_object_instantiation() {
 
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "nested".into(),
-            expr: Box::new(Expr::Class(ExprClass {
-                name: "Outer".into(),
-                params: vec![
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "nested".to_string().into(),
+                expr: Box::new(
                     Expr::Class(ExprClass {
-                        name: "Inner".into(),
+                        name: "Outer".into(),
                         params: vec![
-                            Expr::Lit(ExprLit { value: 1.into() }),
-                            Expr::Lit(ExprLit { value: 2.into() }),
-                        ]
-                    }),
-                    Expr::Class(ExprClass {
-                        name: "Another".into(),
-                        params: vec![]
-                    }),
-                ]
-            }))
-        }))]
+                            Expr::Class(ExprClass {
+                                name: "Inner".into(),
+                                params: vec![
+                                    Expr::Lit(ExprLit { value: 1.into() }).into(),
+                                    Expr::Lit(ExprLit { value: 2.into() }).into(),
+                                ],
+                                named: vec![],
+                            })
+                            .into(),
+                            Expr::Class(ExprClass {
+                                name: "Another".into(),
+                                params: vec![],
+                                named: vec![],
+                            })
+                            .into(),
+                        ],
+                        named: vec![],
+                    })
+                    .into()
+                )
+            })
+            .into()
+        )]
     );
 }
 
@@ -395,28 +509,37 @@ fn test_class_with_collection_params() {
 
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "instance".into(),
-            expr: Box::new(Expr::Class(ExprClass {
-                name: "DataContainer".into(),
-                params: vec![
-                    Expr::Array(ExprArray {
-                        elements: vec![
-                            Expr::Lit(ExprLit { value: 1.into() }),
-                            Expr::Lit(ExprLit { value: 2.into() }),
-                            Expr::Lit(ExprLit { value: 3.into() }),
-                        ],
-                    }),
-                    Expr::Set(ExprSet {
-                        elements: vec![
-                            Expr::Lit(ExprLit { value: 4.into() }),
-                            Expr::Lit(ExprLit { value: 5.into() }),
-                            Expr::Lit(ExprLit { value: 6.into() }),
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "instance".to_string().into(),
+                expr: Box::new(
+                    Expr::Class(ExprClass {
+                        name: "DataContainer".into(),
+                        params: vec![
+                            Expr::Array(ExprArray {
+                                elements: vec![
+                                    Expr::Lit(ExprLit { value: 1.into() }).into(),
+                                    Expr::Lit(ExprLit { value: 2.into() }).into(),
+                                    Expr::Lit(ExprLit { value: 3.into() }).into(),
+                                ],
+                            })
+                            .into(),
+                            Expr::Set(ExprSet {
+                                elements: vec![
+                                    Expr::Lit(ExprLit { value: 4.into() }).into(),
+                                    Expr::Lit(ExprLit { value: 5.into() }).into(),
+                                    Expr::Lit(ExprLit { value: 6.into() }).into(),
+                                ],
+                            })
+                            .into(),
                         ],
-                    }),
-                ]
-            }))
-        }))]
+                        named: vec![],
+                    })
+                    .into()
+                )
+            })
+            .into()
+        )]
     );
 }
 
@@ -427,45 +550,57 @@ fn test_deeply_nested_collections() {
 
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "deep".into(),
-            expr: Box::new(Expr::Array(ExprArray {
-                elements: vec![
-                    Expr::Array(ExprArray {
-                        elements: vec![
-                            Expr::Array(ExprArray {
-                                elements: vec![
-                                    Expr::Lit(ExprLit { value: 1.into() }),
-                                    Expr::Lit(ExprLit { value: 2.into() }),
-                                ],
-                            }),
-                            Expr::Array(ExprArray {
-                                elements: vec![
-                                    Expr::Lit(ExprLit { value: 3.into() }),
-                                    Expr::Lit(ExprLit { value: 4.into() }),
-                                ],
-                            }),
-                        ],
-                    }),
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "deep".to_string().into(),
+                expr: Box::new(
                     Expr::Array(ExprArray {
                         elements: vec![
                             Expr::Array(ExprArray {
                                 elements: vec![
-                                    Expr::Lit(ExprLit { value: 5.into() }),
-                                    Expr::Lit(ExprLit { value: 6.into() }),
+                                    Expr::Array(ExprArray {
+                                        elements: vec![
+                                            Expr::Lit(ExprLit { value: 1.into() }).into(),
+                                            Expr::Lit(ExprLit { value: 2.into() }).into(),
+                                        ],
+                                    })
+                                    .into(),
+                                    Expr::Array(ExprArray {
+                                        elements: vec![
+                                            Expr::Lit(ExprLit { value: 3.into() }).into(),
+                                            Expr::Lit(ExprLit { value: 4.into() }).into(),
+                                        ],
+                                    })
+                                    .into(),
                                 ],
-                            }),
+                            })
+                            .into(),
                             Expr::Array(ExprArray {
                                 elements: vec![
-                                    Expr::Lit(ExprLit { value: 7.into() }),
-                                    Expr::Lit(ExprLit { value: 8.into() }),
+                                    Expr::Array(ExprArray {
+                                        elements: vec![
+                                            Expr::Lit(ExprLit { value: 5.into() }).into(),
+                                            Expr::Lit(ExprLit { value: 6.into() }).into(),
+                                        ],
+                                    })
+                                    .into(),
+                                    Expr::Array(ExprArray {
+                                        elements: vec![
+                                            Expr::Lit(ExprLit { value: 7.into() }).into(),
+                                            Expr::Lit(ExprLit { value: 8.into() }).into(),
+                                        ],
+                                    })
+                                    .into(),
                                 ],
-                            }),
+                            })
+                            .into(),
                         ],
-                    }),
-                ],
-            })),
-        }))]
+                    })
+                    .into()
+                ),
+            })
+            .into()
+        )]
     );
 }
 
@@ -476,10 +611,13 @@ fn test_parenthesized_expressions() {
 
     assert_eq!(
         *scope,
-        vec![Stmt::Item(Item::Const(ItemConst {
-            name: "value".into(),
-            expr: Box::new(Expr::Lit(ExprLit { value: 42.into() })),
-        }))]
+        vec![Stmt::Item(
+            Item::Const(ItemConst {
+                name: "value".to_string().into(),
+                expr: Box::new(Expr::Lit(ExprLit { value: 42.into() }).into()),
+            })
+            .into()
+        )]
     );
 }
 
@@ -495,14 +633,23 @@ fn test_multiple_const_declarations() {
     assert_eq!(scope.len(), 3);
 
     if let [
-        Stmt::Item(Item::Const(first)),
-        Stmt::Item(Item::Const(second)),
-        Stmt::Item(Item::Const(third)),
+        Stmt::Item(Spanned {
+            node: Item::Const(first),
+            ..
+        }),
+        Stmt::Item(Spanned {
+            node: Item::Const(second),
+            ..
+        }),
+        Stmt::Item(Spanned {
+            node: Item::Const(third),
+            ..
+        }),
     ] = scope.as_slice()
     {
-        assert_eq!(first.name, "first");
-        assert_eq!(second.name, "second");
-        assert_eq!(third.name, "third");
+        assert_eq!(*first.name, "first");
+        assert_eq!(*second.name, "second");
+        assert_eq!(*third.name, "third");
     } else {
         panic!("Expected three const declarations");
     }
@@ -518,9 +665,19 @@ fn test_let_declarations() {
 
     assert_eq!(scope.len(), 2);
 
-    if let [Stmt::Item(Item::Let(first)), Stmt::Item(Item::Let(second))] = scope.as_slice() {
-        assert_eq!(first.name, "mutable");
-        assert_eq!(second.name, "anotherMutable");
+    if let [
+        Stmt::Item(Spanned {
+            node: Item::Let(first),
+            ..
+        }),
+        Stmt::Item(Spanned {
+            node: Item::Let(second),
+            ..
+        }),
+    ] = scope.as_slice()
+    {
+        assert_eq!(*first.name, "mutable");
+        assert_eq!(*second.name, "anotherMutable");
     } else {
         panic!("Expected two let declarations");
     }
@@ -536,13 +693,29 @@ fn test_property_declarations() {
 
     assert_eq!(scope.len(), 1);
 
-    if let [Stmt::Item(Item::Object(obj))] = scope.as_slice() {
-        assert_eq!(obj.name, "example");
+    if let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    {
+        assert_eq!(*obj.name, "example");
         assert_eq!(obj.body.len(), 2);
 
-        if let [Item::Property(first), Item::Property(second)] = obj.body.as_slice() {
-            assert_eq!(first.name, "count");
-            assert_eq!(second.name, "name");
+        if let [
+            Spanned {
+                node: Item::Property(first),
+                ..
+            },
+            Spanned {
+                node: Item::Property(second),
+                ..
+            },
+        ] = obj.body.as_slice()
+        {
+            assert_eq!(*first.name, "count");
+            assert_eq!(*second.name, "name");
         } else {
             panic!("Expected two property declarations");
         }
@@ -564,10 +737,1877 @@ fn test_complex_assignment_in_method() {
     assert_eq!(
         *scope,
         vec![stmt!(@item items!(@object "manager", [
-            items!(@let "data", exprs!(@array []).into()),
+            items!(@let "data", exprs!(@array [])),
             items!(@method "updateData", vec![], vec![
                 stmt!(@expr exprs!(@assign exprs!(@field "data", exprs!(@self)), exprs!(@array [exprs!(@lit 1), exprs!(@lit 2), exprs!(@lit 3)])))
             ], false)
         ]))],
     );
 }
+
+#[test]
+fn test_recovers_from_multiple_errors() {
+    let input = r"object foo {
+  property = 1
+  method ok() = 1
+  method broken() {
+    a = )
+    b = 2
+  }
+}
+const = 3
+const fine = 4";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@object "foo", [
+                Item::Error,
+                items!(@method "ok", vec![], vec![stmt!(@expr exprs!(@lit 1))], true),
+                items!(@method "broken", vec![], vec![
+                    stmt!(@expr Expr::Error),
+                    stmt!(@expr exprs!(@assign exprs!(@field "b", exprs!(@self)), exprs!(@lit 2)))
+                ], false)
+            ])),
+            stmt!(@item Item::Error),
+            stmt!(@item items!(@const "fine", exprs!(@lit 4))),
+        ]
+    );
+
+    let messages = diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            r#"Unexpected token "=""#,
+            "Expected expression",
+            r#"Unexpected token "=""#
+        ]
+    );
+    assert_eq!(diagnostics[1].span.from, input.find("= )").unwrap() + 2);
+}
+
+#[test]
+fn test_unclosed_object_reports_eof() {
+    let input = "object foo {\n  method a() = 1\n";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(*scope, vec![stmt!(@item Item::Error)]);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.starts_with("Unexpected EOF"));
+}
+
+#[test]
+fn test_lexer_errors_are_reported() {
+    let input = "const a = ~1\nconst = 2\nconst c = 3 @";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@lit 1))),
+            stmt!(@item Item::Error),
+            stmt!(@item items!(@const "c", exprs!(@lit 3))),
+        ]
+    );
+
+    let messages = diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Unexpected input `~`",
+            r#"Unexpected token "=""#,
+            "Unexpected input `@`"
+        ]
+    );
+}
+
+#[test]
+fn test_nodes_carry_spans() {
+    let input = "const value = 1 + 2\nobject foo {\n  method bar(x) = x\n}";
+    let scope = parse(input);
+
+    let [Stmt::Item(constant), Stmt::Item(object)] = scope.as_slice() else {
+        panic!("Expected two items");
+    };
+    let slice = |span: Span| &input[span.from..span.to];
+
+    assert_eq!(slice(constant.span), "const value = 1 + 2");
+    let Item::Const(ItemConst { expr, .. }) = &constant.node else {
+        panic!("Expected const item");
+    };
+    assert_eq!(slice(expr.span), "1 + 2");
+    let Expr::MethodCall(send) = &expr.node else {
+        panic!("Expected a message send");
+    };
+    assert_eq!(slice(send.receiver.span), "1");
+    assert_eq!(slice(send.args[0].span), "2");
+
+    assert_eq!(slice(object.span), "object foo {\n  method bar(x) = x\n}");
+    let Item::Object(obj) = &object.node else {
+        panic!("Expected object item");
+    };
+    let [method] = obj.body.as_slice() else {
+        panic!("Expected one method");
+    };
+    assert_eq!(slice(method.span), "method bar(x) = x");
+    let Item::Method(method) = &method.node else {
+        panic!("Expected method item");
+    };
+    assert_eq!(slice(method.signature.span), "bar(x)");
+    assert_eq!(slice(method.signature.params[0].span), "x");
+    assert_eq!(slice(method.body.block().unwrap().span), "x");
+}
+
+#[test]
+fn test_declared_and_sent_names_carry_spans() {
+    let input = r#"const total = pepita.energia.max(1)
+program juego { new Ave(energia = 2) }
+test "vuela" {}
+object o { method volar(x) = x }"#;
+    let scope = parse(input);
+    let slice = |span: Span| &input[span.from..span.to];
+
+    let [
+        Stmt::Item(constant),
+        Stmt::Item(program),
+        Stmt::Item(test),
+        Stmt::Item(object),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected four items, got {scope:?}");
+    };
+
+    let Item::Const(ItemConst { name, expr }) = &constant.node else {
+        panic!("Expected a const");
+    };
+    assert_eq!(slice(name.span), "total");
+    let Expr::MethodCall(send) = &expr.node else {
+        panic!("Expected a message send");
+    };
+    assert_eq!(slice(send.name.span), "max");
+    let Expr::Field(field) = &send.receiver.node else {
+        panic!("Expected a field");
+    };
+    assert_eq!(slice(field.name.span), "energia");
+
+    let Item::Program(program) = &program.node else {
+        panic!("Expected a program");
+    };
+    assert_eq!(slice(program.name.span), "juego");
+    let [Stmt::Expr(new)] = program.body.stmts.as_slice() else {
+        panic!("Expected one statement");
+    };
+    let Expr::Class(class) = &new.node else {
+        panic!("Expected an instantiation");
+    };
+    assert_eq!(slice(class.named[0].name.span), "energia");
+
+    let Item::Test(test) = &test.node else {
+        panic!("Expected a test");
+    };
+    assert_eq!(slice(test.name.span), r#""vuela""#);
+
+    let Item::Object(object) = &object.node else {
+        panic!("Expected an object");
+    };
+    let Item::Method(method) = &object.body[0].node else {
+        panic!("Expected a method");
+    };
+    assert_eq!(slice(method.signature.ident.span), "volar");
+}
+
+#[test]
+fn test_item_spans_stop_at_the_closing_brace() {
+    let input =
+        "class A {}\n\n// c\nmixin M {} // m\nobject o {}\n\ndescribe \"d\" {}\n\nconst x = 1";
+    let scope = parse(input);
+    let slice = |span: Span| &input[span.from..span.to];
+
+    let [
+        Stmt::Item(class),
+        Stmt::Item(mixin),
+        Stmt::Item(object),
+        Stmt::Item(describe),
+        Stmt::Item(_),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected five items, got {scope:?}");
+    };
+    assert_eq!((class.span.from, class.span.to), (0, 10));
+    assert_eq!(slice(mixin.span), "mixin M {}");
+    assert_eq!(slice(object.span), "object o {}");
+    assert_eq!(slice(describe.span), "describe \"d\" {}");
+}
+
+#[test]
+fn test_names_and_blocks_carry_spans() {
+    let input = r#"import trenes.{Locomotora as Loco}
+class Ave inherits Animal with Volador {
+  method volar() { return 1 }
+}
+object pepita with Volador {}
+test "vuela" { pepita.volar() }
+const f = { x -> x }"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    let slice = |span: Span| &input[span.from..span.to];
+
+    let [
+        Stmt::Item(import),
+        Stmt::Item(class),
+        Stmt::Item(object),
+        Stmt::Item(test),
+        Stmt::Item(closure),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected five items, got {scope:?}");
+    };
+
+    let Item::Import(ItemImport { module, names, .. }) = &import.node else {
+        panic!("Expected an import");
+    };
+    assert_eq!(slice(module.span), "trenes");
+    assert_eq!(slice(names[0].span), "Locomotora as Loco");
+    assert_eq!(slice(names[0].name.span), "Locomotora");
+    assert_eq!(slice(names[0].alias.as_ref().unwrap().span), "Loco");
+
+    let Item::Class(class) = &class.node else {
+        panic!("Expected a class");
+    };
+    assert_eq!(slice(class.name.span), "Ave");
+    assert_eq!(slice(class.superclass.as_ref().unwrap()[0].span), "Animal");
+    assert_eq!(slice(class.mixins[0].span), "Volador");
+    let Item::Method(method) = &class.body[0].node else {
+        panic!("Expected a method");
+    };
+    assert_eq!(slice(method.body.block().unwrap().span), "{ return 1 }");
+
+    let Item::Object(object) = &object.node else {
+        panic!("Expected an object");
+    };
+    assert_eq!(slice(object.name.span), "pepita");
+    assert_eq!(slice(object.mixins[0].span), "Volador");
+
+    let Item::Test(test) = &test.node else {
+        panic!("Expected a test");
+    };
+    assert_eq!(slice(test.body.span), "{ pepita.volar() }");
+
+    let Item::Const(ItemConst { expr, .. }) = &closure.node else {
+        panic!("Expected a const");
+    };
+    let Expr::Closure(closure) = &expr.node else {
+        panic!("Expected a closure");
+    };
+    assert_eq!(slice(closure.body.span), "{ x -> x }");
+}
+
+#[test]
+fn test_operator_precedence() {
+    let input = "const a = x + 1 > 2 * y ** 2 and y != 0 || z";
+    let scope = parse(input);
+
+    let x_plus_1 = exprs!(@binary Plus, exprs!(@field "x", exprs!(@self)), exprs!(@lit 1));
+    let y_pow_2 = exprs!(@binary Pow, exprs!(@field "y", exprs!(@self)), exprs!(@lit 2));
+    let greater = exprs!(@binary Gt, x_plus_1, exprs!(@binary Multiply, exprs!(@lit 2), y_pow_2));
+    let not_zero = exprs!(@binary Ne, exprs!(@field "y", exprs!(@self)), exprs!(@lit 0));
+    let both = exprs!(@binary "and", greater, not_zero);
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@binary Or, both, exprs!(@field "z", exprs!(@self)))))
+        ]
+    );
+}
+
+#[test]
+fn test_operators_are_left_associative() {
+    let input = "const a = 10 - 4 - 3\nconst b = 7 % 4 ** 2";
+    let scope = parse(input);
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@binary Minus,
+                exprs!(@binary Minus, exprs!(@lit 10), exprs!(@lit 4)),
+                exprs!(@lit 3)
+            ))),
+            stmt!(@item items!(@const "b", exprs!(@binary Pow,
+                exprs!(@binary Modulo, exprs!(@lit 7), exprs!(@lit 4)),
+                exprs!(@lit 2)
+            ))),
+        ]
+    );
+}
+
+#[test]
+fn test_comparison_and_word_operators() {
+    let input = "const a = x === y or x !== y and x <= y\nconst order = x >= y && x < y";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty());
+
+    let same = exprs!(@binary StrictEq, exprs!(@field "x"), exprs!(@field "y"));
+    let not_same = exprs!(@binary StrictNe, exprs!(@field "x"), exprs!(@field "y"));
+    let at_most = exprs!(@binary Le, exprs!(@field "x"), exprs!(@field "y"));
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@binary "or", same, exprs!(@binary "and", not_same, at_most)))),
+            stmt!(@item items!(@const "order", exprs!(@binary And,
+                exprs!(@binary Ge, exprs!(@field "x"), exprs!(@field "y")),
+                exprs!(@binary Lt, exprs!(@field "x"), exprs!(@field "y"))
+            ))),
+        ]
+    );
+}
+
+#[test]
+fn test_unary_operators() {
+    let input = "const a = -x * 2\nconst b = !ready and not -1 < +2";
+    let scope = parse(input);
+
+    // Prefix operators bind tighter than any binary operator
+    let less = exprs!(@binary Lt,
+        exprs!(@unary Not, exprs!(@unary Neg, exprs!(@lit 1))),
+        exprs!(@unary Plus, exprs!(@lit 2))
+    );
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@binary Multiply,
+                exprs!(@unary Neg, exprs!(@field "x")),
+                exprs!(@lit 2)
+            ))),
+            stmt!(@item items!(@const "b", exprs!(@binary "and",
+                exprs!(@unary Not, exprs!(@field "ready")),
+                less
+            ))),
+        ]
+    );
+}
+
+#[test]
+fn test_not_as_message_name() {
+    let input = "object y {\n    method not() = false\n}\nconst a = y.not()\nconst b = not y";
+    let scope = parse(input);
+
+    assert_eq!(
+        scope[1..],
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@send "not", exprs!(@field "y"), []))),
+            stmt!(@item items!(@const "b", exprs!(@unary Not, exprs!(@field "y")))),
+        ]
+    );
+}
+
+#[test]
+fn test_minus_without_spaces_is_subtraction() {
+    let input = "const a = x-1\nconst b = x -1";
+    let scope = parse(input);
+
+    let difference = exprs!(@binary Minus, exprs!(@field "x", exprs!(@self)), exprs!(@lit 1));
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", difference.clone())),
+            stmt!(@item items!(@const "b", difference)),
+        ]
+    );
+}
+
+#[test]
+fn test_inline_if_else() {
+    let input = r#"const mensaje = if (edad >= 18) "Adulto" else "Menor""#;
+    let scope = parse(input);
+
+    let condition = exprs!(@binary Ge, exprs!(@field "edad", exprs!(@self)), exprs!(@lit 18));
+    assert_eq!(
+        *scope,
+        vec![stmt!(@item items!(@const "mensaje", exprs!(@if condition,
+            [stmt!(@expr exprs!(@lit "Adulto"))],
+            exprs!(@block [stmt!(@expr exprs!(@lit "Menor"))])
+        )))]
+    );
+}
+
+#[test]
+fn test_block_if_with_else_if_chain() {
+    let input = r"object semaforo {
+  method accion(color) {
+    if (color == 1) {
+      luz = 1
+    }
+    else if (color == 2) avanzar
+    else {
+      frenar
+    }
+  }
+  method solo(x) {
+    if (x) { a }
+    b
+  }
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let chain = exprs!(@if exprs!(@binary Eq, exprs!(@field "color"), exprs!(@lit 1)),
+        [stmt!(@expr exprs!(@assign exprs!(@field "luz"), exprs!(@lit 1)))],
+        exprs!(@if exprs!(@binary Eq, exprs!(@field "color"), exprs!(@lit 2)),
+            [stmt!(@expr exprs!(@field "avanzar"))],
+            exprs!(@block [stmt!(@expr exprs!(@field "frenar"))])
+        )
+    );
+
+    assert_eq!(
+        *scope,
+        vec![stmt!(@item items!(@object "semaforo", [
+            items!(@method "accion", vec![ident!("color")], vec![stmt!(@expr chain)], false),
+            items!(@method "solo", vec![ident!("x")], vec![
+                stmt!(@expr exprs!(@if exprs!(@field "x"), [stmt!(@expr exprs!(@field "a"))])),
+                stmt!(@expr exprs!(@field "b"))
+            ], false)
+        ]))]
+    );
+}
+
+#[test]
+fn test_block_closures() {
+    let input = r"const doble = { x -> x * 2 }
+const suma = { a, b ->
+  const total = a + b
+  total
+}
+const valor = { 42 }";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "doble", exprs!(@closure [ident!("x")], [
+                stmt!(@expr exprs!(@binary Multiply, exprs!(@field "x"), exprs!(@lit 2)))
+            ]))),
+            stmt!(@item items!(@const "suma", exprs!(@closure [ident!("a"), ident!("b")], [
+                stmt!(@item items!(@const "total", exprs!(@binary Plus, exprs!(@field "a"), exprs!(@field "b")))),
+                stmt!(@expr exprs!(@field "total"))
+            ]))),
+            stmt!(@item items!(@const "valor", exprs!(@closure [], [
+                stmt!(@expr exprs!(@lit 42))
+            ]))),
+        ]
+    );
+}
+
+#[test]
+fn test_arrow_closures() {
+    let input = r"const sumar = (a, b) => a + b
+const obtener = () => 42
+const cuadrado = n => n * n
+const conBloque = n => { n * 2 }
+const agrupado = (a) + 1";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "sumar", exprs!(@closure [ident!("a"), ident!("b")], [
+                stmt!(@expr exprs!(@binary Plus, exprs!(@field "a"), exprs!(@field "b")))
+            ]))),
+            stmt!(@item items!(@const "obtener", exprs!(@closure [], [
+                stmt!(@expr exprs!(@lit 42))
+            ]))),
+            stmt!(@item items!(@const "cuadrado", exprs!(@closure [ident!("n")], [
+                stmt!(@expr exprs!(@binary Multiply, exprs!(@field "n"), exprs!(@field "n")))
+            ]))),
+            stmt!(@item items!(@const "conBloque", exprs!(@closure [ident!("n")], [
+                stmt!(@expr exprs!(@binary Multiply, exprs!(@field "n"), exprs!(@lit 2)))
+            ]))),
+            stmt!(@item items!(@const "agrupado", exprs!(@binary Plus, exprs!(@field "a"), exprs!(@lit 1)))),
+        ]
+    );
+}
+
+#[test]
+fn test_explicit_self_and_super() {
+    let input = r"class Golondrina inherits Ave {
+  override method volar(km) {
+    super(km * 2)
+    self.energia = self.energia - km
+  }
+  method yoMisma() = self
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [
+        Stmt::Item(Spanned {
+            node: Item::Class(class),
+            ..
+        }),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected a class");
+    };
+    let [
+        Spanned {
+            node: Item::PrefixedMethod(volar),
+            ..
+        },
+        Spanned {
+            node: Item::Method(yo_misma),
+            ..
+        },
+    ] = class.body.as_slice()
+    else {
+        panic!("Expected two methods");
+    };
+
+    assert_eq!(
+        volar.method.body.block().unwrap().stmts,
+        vec![
+            stmt!(@expr exprs!(@super [exprs!(@binary Multiply, exprs!(@field "km", exprs!(@self)), exprs!(@lit 2))])),
+            stmt!(@expr exprs!(@assign exprs!(@field "energia"), exprs!(@binary Minus, exprs!(@field "energia"), exprs!(@field "km", exprs!(@self))))),
+        ]
+    );
+    assert_eq!(
+        yo_misma.body.block().unwrap().stmts,
+        vec![stmt!(@expr exprs!(@self))]
+    );
+}
+
+#[test]
+fn test_message_sends() {
+    let input = r"const a = pepita.volar(10).energia()
+const b = calcular(1, 2)
+const c = self.energia
+const d = new Ave().nombre()";
+    let scope = parse(input);
+
+    let pepita = exprs!(@field "pepita", exprs!(@self));
+    let volar = exprs!(@send "volar", pepita, [exprs!(@lit 10)]);
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@send "energia", volar, []))),
+            stmt!(@item items!(@const "b", exprs!(@send "calcular", exprs!(@self), [exprs!(@lit 1), exprs!(@lit 2)]))),
+            stmt!(@item items!(@const "c", exprs!(@field "energia", exprs!(@self)))),
+            stmt!(@item items!(@const "d", exprs!(@send "nombre", exprs!(@class "Ave", vec![]), []))),
+        ]
+    );
+}
+
+#[test]
+fn test_return_statements() {
+    let input = r"object calculadora {
+  method signo(n) {
+    if (n < 0) return -1
+    return if (n == 0) 0 else 1
+  }
+  method nada() {
+    return
+  }
+  method temprano(n) {
+    if (n > 0) { return n }
+  }
+  method muerto() {
+    return 1
+    const x = 2
+    x
+  }
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected an object");
+    };
+    let methods = obj
+        .body
+        .iter()
+        .map(|item| match &item.node {
+            Item::Method(method) => method.body.block().expect("Expected a method body"),
+            _ => panic!("Expected only methods"),
+        })
+        .collect::<Vec<_>>();
+    let [signo, nada, temprano, muerto] = methods.as_slice() else {
+        panic!("Expected four methods");
+    };
+
+    assert_eq!(
+        signo.stmts,
+        vec![
+            stmt!(@expr exprs!(@if exprs!(@binary Lt, exprs!(@field "n"), exprs!(@lit 0)), [
+                stmt!(@expr exprs!(@return exprs!(@unary Neg, exprs!(@lit 1))))
+            ])),
+            stmt!(@expr exprs!(@return exprs!(@if exprs!(@binary Eq, exprs!(@field "n"), exprs!(@lit 0)),
+                [stmt!(@expr exprs!(@lit 0))],
+                exprs!(@block [stmt!(@expr exprs!(@lit 1))])
+            ))),
+        ]
+    );
+    assert_eq!(nada.stmts, vec![stmt!(@expr exprs!(@return))]);
+
+    assert!(signo.always_returns() && signo.unreachable().is_empty());
+    assert!(nada.always_returns());
+    // Returns on some paths only, so a "missing return" can be reported
+    assert!(temprano.has_return() && !temprano.always_returns());
+    // Everything after the return is dead code
+    assert!(muerto.always_returns());
+    let unreachable = muerto.unreachable();
+    assert_eq!(unreachable.len(), 2);
+    assert_eq!(
+        &input[unreachable[0].span().from..unreachable[0].span().to],
+        "const x = 2"
+    );
+}
+
+#[test]
+fn test_try_blocks_and_returns() {
+    let input = r"object o {
+  method ambos() {
+    try { return 1 } catch e { return 2 }
+    const muerto = 3
+  }
+  method soloCuerpo() {
+    try { return 1 } catch e { 2 }
+  }
+  method siempre() {
+    try { 1 } then always { return 3 }
+  }
+  method conTipo() {
+    try { return 1 } catch e : Error { return 2 } then always { 3 }
+  }
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected an object");
+    };
+    let methods = obj
+        .body
+        .iter()
+        .map(|item| match &item.node {
+            Item::Method(method) => method.body.block().expect("Expected a method body"),
+            _ => panic!("Expected only methods"),
+        })
+        .collect::<Vec<_>>();
+    let [ambos, solo_cuerpo, siempre, con_tipo] = methods.as_slice() else {
+        panic!("Expected four methods");
+    };
+
+    assert!(ambos.always_returns());
+    assert_eq!(ambos.unreachable().len(), 1);
+    // The handler may end without returning
+    assert!(solo_cuerpo.has_return() && !solo_cuerpo.always_returns());
+    // `then always` runs on every path
+    assert!(siempre.has_return() && siempre.always_returns());
+    assert!(con_tipo.always_returns());
+}
+
+#[test]
+fn test_throw_and_try_catch() {
+    let input = r#"object banco {
+  method retirar(monto) {
+    try {
+      self.validar(monto)
+    } catch e : MontoInvalido {
+      return 0
+    }
+    catch e : wollok.lang.Exception return -1
+    catch e { throw e }
+    then always { self.cerrar() }
+  }
+  method validar(monto) {
+    if (monto < 0) throw new MontoInvalido("negativo")
+    return try self.chequear(monto)
+  }
+}"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [
+        Stmt::Item(Spanned {
+            node: Item::Object(obj),
+            ..
+        }),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected an object");
+    };
+    let [
+        Spanned {
+            node: Item::Method(retirar),
+            ..
+        },
+        Spanned {
+            node: Item::Method(validar),
+            ..
+        },
+    ] = obj.body.as_slice()
+    else {
+        panic!("Expected two methods");
+    };
+
+    let [
+        Stmt::Expr(Spanned {
+            node: Expr::TryBlock(try_block),
+            ..
+        }),
+    ] = retirar.body.block().unwrap().stmts.as_slice()
+    else {
+        panic!("Expected a try block");
+    };
+    assert_eq!(
+        try_block.block.stmts,
+        vec![stmt!(@expr exprs!(@send "validar", exprs!(@self), [exprs!(@field "monto")]))]
+    );
+
+    let kinds = try_block
+        .catches
+        .iter()
+        .map(|catch| (catch.param.name.as_str(), catch.kind.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            ("e", Some("MontoInvalido")),
+            ("e", Some("wollok.lang.Exception")),
+            ("e", None)
+        ]
+    );
+    assert_eq!(
+        try_block.catches[2].body.stmts,
+        vec![stmt!(@expr exprs!(@throw exprs!(@field "e", exprs!(@self))))]
+    );
+    assert_eq!(
+        try_block.always.as_ref().map(|always| &always.stmts),
+        Some(&vec![
+            stmt!(@expr exprs!(@send "cerrar", exprs!(@self), []))
+        ])
+    );
+
+    assert_eq!(
+        validar.body.block().unwrap().stmts,
+        vec![
+            stmt!(@expr exprs!(@if exprs!(@binary Lt, exprs!(@field "monto"), exprs!(@lit 0)), [
+                stmt!(@expr exprs!(@throw exprs!(@class "MontoInvalido", vec![exprs!(@lit "negativo")])))
+            ])),
+            stmt!(@expr exprs!(@return exprs!(@try exprs!(@send "chequear", exprs!(@self), [exprs!(@field "monto")])))),
+        ]
+    );
+}
+
+#[test]
+fn test_then_and_always_are_names_outside_try() {
+    let input = "const always = 3\nconst then = always\nconst a = promesa.then(always)";
+    let scope = parse(input);
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "always", exprs!(@lit 3))),
+            stmt!(@item items!(@const "then", exprs!(@field "always"))),
+            stmt!(@item items!(@const "a", exprs!(@send "then", exprs!(@field "promesa"), [exprs!(@field "always")]))),
+        ]
+    );
+}
+
+#[test]
+fn test_try_block_needs_a_handler() {
+    let input = "const a = try { 1 }\nconst b = 2";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Error),
+            stmt!(@item items!(@const "b", exprs!(@lit 2))),
+        ]
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected `catch` or `then always` after try block"
+    );
+}
+
+#[test]
+fn test_propagate_and_assert_postfix() {
+    let input = r"const a = validar(datos)?.valor()
+const b = dividir(x, y)!
+const c = listo! && !roto";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let validar = exprs!(@send "validar", exprs!(@self), [exprs!(@field "datos")]);
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@send "valor", exprs!(@propagate validar), []))),
+            stmt!(@item items!(@const "b", exprs!(@assert exprs!(@send "dividir", exprs!(@self), [exprs!(@field "x"), exprs!(@field "y")])))),
+            stmt!(@item items!(@const "c", exprs!(@binary And,
+                exprs!(@assert exprs!(@field "listo")),
+                exprs!(@unary Not, exprs!(@field "roto"))
+            ))),
+        ]
+    );
+}
+
+#[test]
+fn test_import_declarations() {
+    let input = r"import trenes.*
+import colecciones.{Lista, Set as Conjunto}
+import io.Console as Consola
+import deposito";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Import(ItemImport {
+                module: "trenes".to_string().into(),
+                wildcard: true,
+                names: vec![],
+            })),
+            stmt!(@item Item::Import(ItemImport {
+                module: "colecciones".to_string().into(),
+                wildcard: false,
+                names: vec![
+                    ImportName {
+                        name: "Lista".to_string().into(),
+                        alias: None,
+                    }
+                    .into(),
+                    ImportName {
+                        name: "Set".to_string().into(),
+                        alias: Some("Conjunto".to_string().into()),
+                    }
+                    .into(),
+                ],
+            })),
+            stmt!(@item Item::Import(ItemImport {
+                module: "io".to_string().into(),
+                wildcard: false,
+                names: vec![
+                    ImportName {
+                        name: "Console".to_string().into(),
+                        alias: Some("Consola".to_string().into()),
+                    }
+                    .into(),
+                ],
+            })),
+            stmt!(@item Item::Import(ItemImport {
+                module: "deposito".to_string().into(),
+                wildcard: false,
+                names: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_import_of_a_single_module() {
+    let input = "import deposito
+const a = deposito.Caja";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let Stmt::Item(import) = &scope[0] else {
+        panic!("Expected an import");
+    };
+    assert_eq!(
+        import.node,
+        Item::Import(ItemImport {
+            module: "deposito".to_string().into(),
+            wildcard: false,
+            names: vec![],
+        })
+    );
+    assert_eq!(&input[import.span.from..import.span.to], "import deposito");
+}
+
+#[test]
+fn test_as_is_a_name_outside_imports() {
+    let input = "import io.Console as Consola\nconst as = 1\nconst b = tipo.as(as)";
+    let scope = parse(input);
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Import(ItemImport {
+                module: "io".to_string().into(),
+                wildcard: false,
+                names: vec![
+                    ImportName {
+                        name: "Console".to_string().into(),
+                        alias: Some("Consola".to_string().into()),
+                    }
+                    .into()
+                ],
+            })),
+            stmt!(@item items!(@const "as", exprs!(@lit 1))),
+            stmt!(@item items!(@const "b", exprs!(@send "as", exprs!(@field "tipo"), [exprs!(@field "as")]))),
+        ]
+    );
+}
+
+#[test]
+fn test_import_needs_a_name_after_dot() {
+    let input = "import trenes.\nconst a = 1";
+    let (_, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert_eq!(diagnostics[0].message, "Unexpected token \"\\n\"");
+}
+
+#[test]
+fn test_describe_with_fixtures_and_tests() {
+    let input = r#"describe "pila" {
+    let pila = []
+
+    setup {
+        pila.add(1)
+    }
+
+    after {
+        pila.clear()
+    }
+
+    test "tiene un elemento" {
+        assert.equals(1, pila.size())
+    }
+
+    only test "no esta vacia" {
+        assert.notThat(pila.isEmpty())
+    }
+
+    describe "anidado" {
+        const vacia = []
+    }
+}"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let expected = Item::Describe(ItemDescribe {
+        name: "pila".to_string().into(),
+        body: vec![
+            items!(@let "pila", exprs!(@array [])).into(),
+            Item::Fixture(ItemFixture {
+                kind: FixtureKind::Setup,
+                body: Block {
+                    stmts: vec![stmt!(@expr exprs!(@send "add", exprs!(@field "pila", exprs!(@self)), [exprs!(@lit 1)]))],
+                }
+                .into(),
+            })
+            .into(),
+            Item::Fixture(ItemFixture {
+                kind: FixtureKind::After,
+                body: Block {
+                    stmts: vec![stmt!(@expr exprs!(@send "clear", exprs!(@field "pila", exprs!(@self)), []))],
+                }
+                .into(),
+            })
+            .into(),
+            Item::Test(ItemTest {
+                name: "tiene un elemento".to_string().into(),
+                only: false,
+                body: Block {
+                    stmts: vec![stmt!(@expr exprs!(@send
+                        "equals",
+                        exprs!(@field "assert", exprs!(@self)),
+                        [exprs!(@lit 1), exprs!(@send "size", exprs!(@field "pila", exprs!(@self)), [])]
+                    ))],
+                }
+                .into(),
+            })
+            .into(),
+            Item::Test(ItemTest {
+                name: "no esta vacia".to_string().into(),
+                only: true,
+                body: Block {
+                    stmts: vec![stmt!(@expr exprs!(@send
+                        "notThat",
+                        exprs!(@field "assert", exprs!(@self)),
+                        [exprs!(@send "isEmpty", exprs!(@field "pila", exprs!(@self)), [])]
+                    ))],
+                }
+                .into(),
+            })
+            .into(),
+            Item::Describe(ItemDescribe {
+                name: "anidado".to_string().into(),
+                body: vec![items!(@const "vacia", exprs!(@array [])).into()],
+            })
+            .into(),
+        ],
+    });
+    assert_eq!(*scope, vec![stmt!(@item expected)]);
+}
+
+#[test]
+fn test_describe_recovers_from_broken_tests() {
+    let input = r#"describe "roto" {
+    test sinNombre {
+    }
+    test "sano" {
+    }
+}"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(diagnostics[0].message, "Unexpected token \"sinNombre\"");
+    let Stmt::Item(Spanned {
+        node: Item::Describe(describe),
+        ..
+    }) = &scope[0]
+    else {
+        panic!("Expected describe, got {:?}", scope[0]);
+    };
+    assert!(matches!(
+        describe.body.last().map(|item| &item.node),
+        Some(Item::Test(ItemTest { name, .. })) if **name == "sano"
+    ));
+}
+
+#[test]
+fn test_only_test_at_top_level() {
+    let input = r#"only test "solo este" {
+}
+only.size()"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Test(ItemTest {
+                name: "solo este".to_string().into(),
+                only: true,
+                body: Block { stmts: vec![] }.into(),
+            })),
+            // Anywhere else `only` is just a name
+            stmt!(@expr exprs!(@send "size", exprs!(@field "only", exprs!(@self)), [])),
+        ]
+    );
+}
+
+#[test]
+fn test_top_level_declarations_are_rejected_inside_bodies() {
+    let input = r#"object pepita {
+    method volar() {
+        test "adentro" {}
+        describe "adentro" {}
+    }
+}
+const f = { x -> import a.b
+    x }
+program p {
+    only test "x" {}
+    mixin M {}
+    package a {}
+    program q {}
+}
+describe "d" {
+    test "vale" {}
+}"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    let messages = diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        [
+            "Tests can only be declared at the top level or inside a describe",
+            "Tests can only be declared at the top level or inside a describe",
+            "Imports can only be declared at the top level",
+            "Tests can only be declared at the top level or inside a describe",
+            "Mixins can only be declared at the top level",
+            "Packages can only be declared at the top level",
+            "Programs can only be declared at the top level",
+        ]
+    );
+    assert_eq!(scope.len(), 4);
+}
+
+#[test]
+fn test_program_and_package() {
+    let input = r"package juegos.tateti {
+    object tablero {}
+    const jugadores = 2
+}
+
+program principal {
+    tablero.iniciar()
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let iniciar = exprs!(@send "iniciar", exprs!(@field "tablero", exprs!(@self)), []);
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Package(ItemPackage {
+                name: "juegos.tateti".to_string().into(),
+                body: vec![
+                    items!(@object "tablero", []).into(),
+                    items!(@const "jugadores", exprs!(@lit 2)).into(),
+                ],
+            })),
+            stmt!(@item Item::Program(ItemProgram {
+                name: "principal".to_string().into(),
+                body: Block {
+                    stmts: vec![stmt!(@expr iniciar)],
+                }
+                .into(),
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_package_rejects_expressions() {
+    let input = "package a {\n    1 + 2\n    const b = 3\n}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected a declaration inside package"
+    );
+    let Stmt::Item(Spanned {
+        node: Item::Package(package),
+        ..
+    }) = &scope[0]
+    else {
+        panic!("Expected package, got {:?}", scope[0]);
+    };
+    assert_eq!(
+        package.body.last().map(|item| &item.node),
+        Some(&items!(@const "b", exprs!(@lit 3)))
+    );
+}
+
+#[test]
+fn test_mixins_and_with_composition() {
+    let input = r"mixin Mostrable {
+    method mostrar() {
+        console.println(self.toString())
+    }
+}
+class Persona inherits Ser with Mostrable, Comparable {}
+object pepita with Mostrable {}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let println = exprs!(@send "println", exprs!(@field "console", exprs!(@self)), [
+        exprs!(@send "toString", exprs!(@self), [])
+    ]);
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Mixin(ItemMixin {
+                name: "Mostrable".to_string().into(),
+                body: vec![items!(@method "mostrar", vec![], vec![stmt!(@expr println)], false).into()],
+            })),
+            stmt!(@item Item::Class(ItemClass {
+                name: "Persona".to_string().into(),
+                superclass: Some(vec!["Ser".to_string().into()]),
+                mixins: vec!["Mostrable".to_string().into(), "Comparable".to_string().into()],
+                body: vec![],
+                doc: None,
+            })),
+            stmt!(@item Item::Object(ItemObject {
+                name: "pepita".to_string().into(),
+                superclass: None,
+                mixins: vec!["Mostrable".to_string().into()],
+                body: vec![],
+                doc: None,
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_with_needs_a_mixin() {
+    let input = "object pepita with {}";
+    let (_, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert_eq!(diagnostics[0].message, "Unexpected token \"{\"");
+}
+
+#[test]
+fn test_with_is_a_name_outside_mixin_lists() {
+    let input = "const with = 1\nconst b = lista.with(with)";
+    let scope = parse(input);
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "with", exprs!(@lit 1))),
+            stmt!(@item items!(@const "b", exprs!(@send "with", exprs!(@field "lista"), [exprs!(@field "with")]))),
+        ]
+    );
+}
+
+#[test]
+fn test_abstract_and_native_methods() {
+    let input = r"class Figura {
+    method area()
+    abstract method perimetro(escala) // lo define cada figura
+    native method identity()
+    method doble() = self.area() * 2
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [
+        Stmt::Item(Spanned {
+            node: Item::Class(class),
+            ..
+        }),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected a class");
+    };
+    let bodies = class
+        .body
+        .iter()
+        .map(|item| match &item.node {
+            Item::Method(ItemMethod {
+                signature, body, ..
+            }) => (signature.ident.as_str(), body),
+            _ => panic!("Expected only methods"),
+        })
+        .collect::<Vec<_>>();
+    let [area, perimetro, identity, doble] = bodies.as_slice() else {
+        panic!("Expected four methods");
+    };
+
+    assert_eq!(*area, ("area", &MethodBody::Abstract));
+    assert_eq!(*perimetro, ("perimetro", &MethodBody::Abstract));
+    assert_eq!(*identity, ("identity", &MethodBody::Native));
+    assert!(matches!(doble, ("doble", MethodBody::Inline(_))));
+}
+
+#[test]
+fn test_abstract_methods_cant_have_a_body() {
+    let input = "class Figura {\n    abstract method area() = 0\n}";
+    let (_, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert_eq!(diagnostics[0].message, "abstract methods can't have a body");
+}
+
+#[test]
+fn test_operator_methods() {
+    let input = r"mixin Comparable {
+    method <(otro) = self.compareTo(otro) < 0
+    method <=(otro) = self.compareTo(otro) <= 0
+    method ==(otro) = self.compareTo(otro) == 0
+    method +(otro) = self.sumar(otro)
+    method and(otro) = otro
+    abstract method compareTo(otro)
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [
+        Stmt::Item(Spanned {
+            node: Item::Mixin(mixin),
+            ..
+        }),
+    ] = scope.as_slice()
+    else {
+        panic!("Expected a mixin");
+    };
+    let selectors = mixin
+        .body
+        .iter()
+        .map(|item| match &item.node {
+            Item::Method(method) => method.signature.ident.as_str(),
+            _ => panic!("Expected only methods"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(selectors, ["<", "<=", "==", "+", "and", "compareTo"]);
+}
+
+#[test]
+fn test_binary_operators_are_message_sends() {
+    let input = "const a = x + 1";
+    let scope = parse(input);
+
+    assert_eq!(
+        *scope,
+        vec![stmt!(@item items!(@const "a", exprs!(@send
+            "+",
+            exprs!(@field "x", exprs!(@self)),
+            [exprs!(@lit 1)]
+        )))]
+    );
+}
+
+#[test]
+fn test_word_and_symbol_operators_are_different_selectors() {
+    let input = r"object condicion {
+    method and(otra) = true
+    method &&(otra) = false
+    method or(otra) = true
+}
+const a = condicion and x
+const b = condicion && x
+const c = condicion or x";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [Stmt::Item(object), consts @ ..] = scope.as_slice() else {
+        panic!("Expected an object and consts, got {scope:?}");
+    };
+    let Item::Object(ItemObject { body, .. }) = &object.node else {
+        panic!("Expected an object");
+    };
+    let declared = body
+        .iter()
+        .map(|item| match &item.node {
+            Item::Method(method) => method.signature.ident.as_str(),
+            _ => panic!("Expected only methods"),
+        })
+        .collect::<Vec<_>>();
+    let sent = consts
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Item(Spanned {
+                node: Item::Const(ItemConst { expr, .. }),
+                ..
+            }) => match &expr.node {
+                Expr::MethodCall(send) => send.name.as_str(),
+                _ => panic!("Expected a message send"),
+            },
+            _ => panic!("Expected a const"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(declared, ["and", "&&", "or"]);
+    assert_eq!(sent, declared);
+}
+
+#[test]
+fn test_word_operators_as_message_names() {
+    let input = "const a = b.and(c)\nconst o = b.or(c)";
+    let scope = parse(input);
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@send "and", exprs!(@field "b"), [exprs!(@field "c")]))),
+            stmt!(@item items!(@const "o", exprs!(@send "or", exprs!(@field "b"), [exprs!(@field "c")]))),
+        ]
+    );
+}
+
+#[test]
+fn test_short_circuit_operators_send_a_closure() {
+    let input = "const a = x && y\nconst b = x * y";
+    let scope = parse(input);
+
+    // Only `&&`, `||`, `and` and `or` delay their right side
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "a", exprs!(@send
+                "&&",
+                exprs!(@field "x"),
+                [exprs!(@closure [], [stmt!(@expr exprs!(@field "y"))])]
+            ))),
+            stmt!(@item items!(@const "b", exprs!(@send
+                "*",
+                exprs!(@field "x"),
+                [exprs!(@field "y")]
+            ))),
+        ]
+    );
+}
+
+#[test]
+fn test_declarations_without_initializer() {
+    let input = r"class Persona {
+    property nombre
+    const property dni
+    let property edad = 0
+    let total // se calcula después
+    method saludar() {
+        let saludo
+        saludo = nombre
+    }
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![stmt!(@item Item::Class(ItemClass {
+            name: "Persona".to_string().into(),
+            superclass: None,
+            mixins: vec![],
+            body: vec![
+                Item::Property(ItemProperty {
+                    name: "nombre".to_string().into(),
+                    expr: None,
+                    constant: false,
+                    doc: None,
+                })
+                .into(),
+                Item::Property(ItemProperty {
+                    name: "dni".to_string().into(),
+                    expr: None,
+                    constant: true,
+                    doc: None,
+                })
+                .into(),
+                Item::Property(ItemProperty {
+                    name: "edad".to_string().into(),
+                    expr: Some(Box::new(exprs!(@lit 0).into())),
+                    constant: false,
+                    doc: None,
+                })
+                .into(),
+                items!(@let "total").into(),
+                items!(@method "saludar", vec![], vec![
+                    stmt!(@item items!(@let "saludo")),
+                    stmt!(@expr exprs!(@assign exprs!(@field "saludo", exprs!(@self)), exprs!(@field "nombre", exprs!(@self)))),
+                ], false).into(),
+            ],
+            doc: None,
+        }))]
+    );
+}
+
+#[test]
+fn test_declaration_needs_equals_before_value() {
+    let input = "let total 5\nconst b = 1";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(
+        diagnostics[0].message,
+        "Expected '=' or the end of the declaration"
+    );
+    assert_eq!(
+        scope.last(),
+        Some(&stmt!(@item items!(@const "b", exprs!(@lit 1))))
+    );
+}
+
+#[test]
+fn test_new_with_named_arguments() {
+    let input = r#"const pepita = new Ave(
+    energia = 10,
+    nombre = "Pepita",
+)
+const libro = new Libro(1, x == 2)"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "pepita", exprs!(@new "Ave", [
+                "energia" => exprs!(@lit 10),
+                "nombre" => exprs!(@lit "Pepita")
+            ]))),
+            stmt!(@item items!(@const "libro", exprs!(@class "Libro", vec![
+                exprs!(@lit 1),
+                exprs!(@binary Eq, exprs!(@field "x", exprs!(@self)), exprs!(@lit 2))
+            ]))),
+        ]
+    );
+}
+
+#[test]
+fn test_named_arguments_match_class_attributes() {
+    let input = r"mixin Nombrable {
+    property nombre
+}
+class Animal with Nombrable {
+    let patas = 4
+}
+class Ave inherits Animal {
+    property energia
+    const color = 'gris'
+}
+const a = new Ave(energia = 10, altura = 3, energia = 5, patas = 2, nombre = 'pepita')
+const b = new Golondrina(energia = 1, energia = 2, alas = 2)
+object pepita inherits Ave(plumas = 100) {}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    let [Stmt::Item(mixin), Stmt::Item(animal), Stmt::Item(ave), ..] = scope.as_slice() else {
+        panic!("Expected the declarations first, got {scope:?}");
+    };
+    let (Item::Mixin(mixin), Item::Class(animal), Item::Class(ave)) =
+        (&mixin.node, &animal.node, &ave.node)
+    else {
+        panic!("Expected a mixin and two classes");
+    };
+    assert_eq!(mixin.attributes().collect::<Vec<_>>(), ["nombre"]);
+    assert_eq!(animal.attributes().collect::<Vec<_>>(), ["patas"]);
+    assert_eq!(ave.attributes().collect::<Vec<_>>(), ["energia", "color"]);
+
+    let slice = |span: Span| &input[span.from..span.to];
+    let reported = diagnostics
+        .iter()
+        .map(|d| (d.message.as_str(), slice(d.span)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reported,
+        [
+            ("Class `Ave` has no attribute `altura`", "altura = 3"),
+            ("Attribute `energia` is initialized twice", "energia = 5"),
+            // `Golondrina` is not declared here, so only the repetition is known
+            ("Attribute `energia` is initialized twice", "energia = 2"),
+            ("Class `Ave` has no attribute `plumas`", "plumas = 100"),
+        ]
+    );
+}
+
+#[test]
+fn test_object_inherits_with_arguments() {
+    let input = r#"object pepita inherits Ave(energia = 10) with Voladora {
+    method volar() = energia
+}
+object pepona inherits Ave {}
+object pepon inherits Ave("Pepon", 5) {}"#;
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Object(ItemObject {
+                name: "pepita".to_string().into(),
+                superclass: Some(
+                    ExprClass {
+                        name: "Ave".to_string(),
+                        params: vec![],
+                        named: vec![NamedArg {
+                            name: "energia".to_string().into(),
+                            value: exprs!(@lit 10).into(),
+                        }
+                        .into()],
+                    }
+                    .into()
+                ),
+                mixins: vec!["Voladora".to_string().into()],
+                body: vec![items!(@method "volar", vec![], vec![
+                    stmt!(@expr exprs!(@field "energia", exprs!(@self)))
+                ], true)
+                .into()],
+                doc: None,
+            })),
+            stmt!(@item Item::Object(ItemObject {
+                name: "pepona".to_string().into(),
+                superclass: Some(
+                    ExprClass {
+                        name: "Ave".to_string(),
+                        params: vec![],
+                        named: vec![],
+                    }
+                    .into()
+                ),
+                mixins: vec![],
+                body: vec![],
+                doc: None,
+            })),
+            stmt!(@item Item::Object(ItemObject {
+                name: "pepon".to_string().into(),
+                superclass: Some(
+                    ExprClass {
+                        name: "Ave".to_string(),
+                        params: vec![exprs!(@lit "Pepon").into(), exprs!(@lit 5).into()],
+                        named: vec![],
+                    }
+                    .into()
+                ),
+                mixins: vec![],
+                body: vec![],
+                doc: None,
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_objects_override_inherited_methods() {
+    let input = r"object pepita inherits Ave {
+    override method volar() = 1
+}
+const o = object inherits Ave { override method volar() = 2 }";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item Item::Object(ItemObject {
+                name: "pepita".to_string().into(),
+                superclass: Some(
+                    ExprClass {
+                        name: "Ave".to_string(),
+                        params: vec![],
+                        named: vec![],
+                    }
+                    .into()
+                ),
+                mixins: vec![],
+                body: vec![Item::PrefixedMethod(ItemPrefixedMethod {
+                    prefix: Prefix::Override,
+                    method: ItemMethod {
+                        signature: Signature {
+                            ident: "volar".to_string().into(),
+                            params: vec![],
+                        }
+                        .into(),
+                        body: MethodBody::Inline(
+                            Block {
+                                stmts: vec![stmt!(@expr exprs!(@lit 1))],
+                            }
+                            .into()
+                        ),
+                        doc: None,
+                    },
+                })
+                .into()],
+                doc: None,
+            })),
+            stmt!(@item items!(@const "o", Expr::Object(ExprObject {
+                superclass: Some(
+                    ExprClass {
+                        name: "Ave".to_string(),
+                        params: vec![],
+                        named: vec![],
+                    }
+                    .into()
+                ),
+                mixins: vec![],
+                body: vec![Item::PrefixedMethod(ItemPrefixedMethod {
+                    prefix: Prefix::Override,
+                    method: ItemMethod {
+                        signature: Signature {
+                            ident: "volar".to_string().into(),
+                            params: vec![],
+                        }
+                        .into(),
+                        body: MethodBody::Inline(
+                            Block {
+                                stmts: vec![stmt!(@expr exprs!(@lit 2))],
+                            }
+                            .into()
+                        ),
+                        doc: None,
+                    },
+                })
+                .into()],
+            }))),
+        ]
+    );
+}
+
+#[test]
+fn test_anonymous_objects() {
+    let input = r"const o = object { method f() = 1 }
+const p = object inherits Ave(energia = 1) {
+    property nombre
+}.nombre()";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "o", Expr::Object(ExprObject {
+                superclass: None,
+                mixins: vec![],
+                body: vec![items!(@method "f", vec![], vec![stmt!(@expr exprs!(@lit 1))], true).into()],
+            }))),
+            stmt!(@item items!(@const "p", exprs!(@send "nombre", Expr::Object(ExprObject {
+                superclass: Some(
+                    ExprClass {
+                        name: "Ave".to_string(),
+                        params: vec![],
+                        named: vec![NamedArg {
+                            name: "energia".to_string().into(),
+                            value: exprs!(@lit 1).into(),
+                        }
+                        .into()],
+                    }
+                    .into()
+                ),
+                mixins: vec![],
+                body: vec![Item::Property(ItemProperty {
+                    name: "nombre".to_string().into(),
+                    expr: None,
+                    constant: false,
+                    doc: None,
+                })
+                .into()],
+            }), []))),
+        ]
+    );
+}
+
+#[test]
+fn test_statement_starting_with_an_anonymous_object() {
+    let input = "object { method f() = 1 }.f()\nobject pepita {}";
+    let scope = parse(input);
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@expr exprs!(@send "f", Expr::Object(ExprObject {
+                superclass: None,
+                mixins: vec![],
+                body: vec![items!(@method "f", vec![], vec![stmt!(@expr exprs!(@lit 1))], true).into()],
+            }), [])),
+            stmt!(@item items!(@object "pepita", [])),
+        ]
+    );
+}
+
+#[test]
+fn test_doc_comments_attach_to_items() {
+    let input = r"/** Un ave que vuela */
+class Ave {
+    /**
+     * Energía en joules
+     */
+    property energia = 100
+
+    // No es documentación
+    /** Gasta energía */
+    override method volar() { energia = energia - 10 }
+}
+
+/** La golondrina */
+object pepita inherits Ave {
+    method comer() {}
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [Stmt::Item(class), Stmt::Item(object)] = scope.as_slice() else {
+        panic!("Expected two items, got {scope:?}");
+    };
+    let Item::Class(ItemClass { body, .. }) = &class.node else {
+        panic!("Expected a class");
+    };
+    let Item::Object(ItemObject { body: methods, .. }) = &object.node else {
+        panic!("Expected an object");
+    };
+
+    assert_eq!(class.doc(), Some("Un ave que vuela"));
+    assert_eq!(body[0].doc(), Some("Energía en joules"));
+    assert_eq!(body[1].doc(), Some("Gasta energía"));
+    assert_eq!(object.doc(), Some("La golondrina"));
+    assert_eq!(methods[0].doc(), None);
+}
+
+#[test]
+fn test_block_comments_are_trivia() {
+    let input = r"object contador {
+    /* un comentario
+       de varias /* anidadas */ líneas */
+    property total = 1 /* en línea */ + 2
+    /** Documenta nada */
+}
+
+/** Tampoco documenta */
+contador.total()
+class Vacia {}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [Stmt::Item(object), Stmt::Expr(_), Stmt::Item(class)] = scope.as_slice() else {
+        panic!("Expected object, expression and class, got {scope:?}");
+    };
+    let Item::Object(ItemObject { body, .. }) = &object.node else {
+        panic!("Expected an object");
+    };
+
+    assert_eq!(body.len(), 1);
+    assert_eq!(body[0].doc(), None);
+    assert_eq!(class.doc(), None);
+}
+
+#[test]
+fn test_string_escapes_and_multiline_strings() {
+    let input =
+        "const saludo = \"dijo \\\"hola\\\"\\n\"\nconst poema = 'una\ndos'\nconst mal = \"\\q\"";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+
+    assert_eq!(
+        *scope,
+        vec![
+            stmt!(@item items!(@const "saludo", exprs!(@lit "dijo \"hola\"\n"))),
+            stmt!(@item items!(@const "poema", exprs!(@lit "una\ndos"))),
+            // The bad escape is kept as written
+            stmt!(@item items!(@const "mal", exprs!(@lit "\\q"))),
+        ]
+    );
+    let [escape] = diagnostics.as_slice() else {
+        panic!("Expected only the invalid escape, got {diagnostics:?}");
+    };
+    assert_eq!(escape.message, "Invalid escape sequence `\\q`");
+    assert_eq!(&input[escape.span.from..escape.span.to], "\\q");
+}
+
+#[test]
+fn test_method_prefixes() {
+    let input = r"class Cuenta {
+    override method saldo() = 0
+    fallible method retirar(monto) {}
+    override fallible method depositar(monto) {}
+}";
+    let (scope, diagnostics) = Scope::parse(input, TokenStream::new(input));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let [Stmt::Item(class)] = scope.as_slice() else {
+        panic!("Expected a class, got {scope:?}");
+    };
+    let Item::Class(ItemClass { body, .. }) = &class.node else {
+        panic!("Expected a class");
+    };
+    let prefixes = body
+        .iter()
+        .map(|item| match &item.node {
+            Item::PrefixedMethod(ItemPrefixedMethod { prefix, method }) => {
+                (prefix.clone(), method.signature.ident.as_str())
+            }
+            _ => panic!("Expected only prefixed methods"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        prefixes,
+        vec![
+            (Prefix::Override, "saldo"),
+            (Prefix::Fallible, "retirar"),
+            (Prefix::OverrideFallible, "depositar"),
+        ]
+    );
+}
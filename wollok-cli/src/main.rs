@@ -32,7 +32,10 @@ fn main() {
 
             // Intentar parsear AST
             let token_stream = wollok_lexer::lexer::TokenStream::new(&content);
-            let _ = wollok_ast::ast::Scope::from_tokens("example.wlk", token_stream);
+            let (_, diagnostics) = wollok_ast::ast::Scope::parse(&content, token_stream);
+            for diagnostic in &diagnostics {
+                diagnostic.eprint(&content);
+            }
             info!("AST parsing completed");
         } else {
             info!("Lexing failed");
@@ -25,20 +25,30 @@ pub enum Expr {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BinaryOp {
-    Eq,  // ==
-    Ne,  // !=
-    And, // &&
-    Or,  // ||
+    Eq,       // ==
+    Ne,       // !=
+    StrictEq, // ===
+    StrictNe, // !==
+    Lt,       // <
+    Gt,       // >
+    Le,       // <=
+    Ge,       // >=
+    And,      // && | and
+    Or,       // || | or
 
     Plus,     // +
     Minus,    // -
     Multiply, // *
     Div,      // /
+    Modulo,   // %
+    Pow,      // **
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UnaryOp {
-    Not,
+    Not,  // ! | not
+    Neg,  // -
+    Plus, // +
 }
 
 impl From<i64> for Expr {
@@ -55,13 +65,13 @@ impl From<f64> for Expr {
 
 impl From<&str> for Expr {
     fn from(val: &str) -> Self {
-        Expr::Literal(Literal::String(val.to_owned()))
+        Expr::Literal(Literal::String(val.to_owned().into()))
     }
 }
 
 impl From<String> for Expr {
     fn from(val: String) -> Self {
-        Expr::Literal(Literal::String(val))
+        Expr::Literal(Literal::String(val.into()))
     }
 }
 
@@ -71,21 +81,34 @@ impl From<bool> for Expr {
     }
 }
 
-impl fmt::Display for BinaryOp {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let op = match self {
+impl BinaryOp {
+    /// The name of the method this operator sends: `a + b` is `a.+(b)`
+    #[must_use]
+    pub fn selector(&self) -> &'static str {
+        match self {
             BinaryOp::Eq => "==",
             BinaryOp::Ne => "!=",
+            BinaryOp::StrictEq => "===",
+            BinaryOp::StrictNe => "!==",
+            BinaryOp::Lt => "<",
+            BinaryOp::Gt => ">",
+            BinaryOp::Le => "<=",
+            BinaryOp::Ge => ">=",
             BinaryOp::And => "&&",
             BinaryOp::Or => "||",
             BinaryOp::Plus => "+",
             BinaryOp::Minus => "-",
             BinaryOp::Multiply => "*",
             BinaryOp::Div => "/",
+            BinaryOp::Modulo => "%",
+            BinaryOp::Pow => "**",
         }
-        .bright_red()
-        .to_string();
-        write!(f, "{op}")
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.selector().bright_red())
     }
 }
 
@@ -93,6 +116,8 @@ impl fmt::Display for UnaryOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let op = match self {
             UnaryOp::Not => "!",
+            UnaryOp::Neg => "-",
+            UnaryOp::Plus => "+",
         }
         .bright_red()
         .to_string();
@@ -1,7 +1,10 @@
 use {
     crate::token::Span,
     ariadne::{Label, Report, ReportKind, Source},
-    std::{backtrace::Backtrace, fmt},
+    std::{
+        backtrace::{Backtrace, BacktraceStatus},
+        fmt,
+    },
     winnow::{
         LocatingSlice,
         error::{AddContext, ParserError},
@@ -20,17 +23,37 @@ pub struct LexerErr<'e> {
     pub backtrace: Backtrace,
 }
 
+impl<'e> LexerErr<'e> {
+    #[must_use]
+    pub fn new(base: &'e str, span: Span, msg: impl fmt::Display) -> Self {
+        Self {
+            base,
+            span,
+            labels: vec![(span, msg.to_string())],
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Mensaje principal del error (la primera etiqueta), si existe
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        self.labels.first().map(|(_, msg)| msg.as_str())
+    }
+}
+
 pub trait LexerExt<'lex> {
     type Base;
     type Span;
+    type Error;
     fn base(&self) -> Self::Base;
     fn span(&self) -> Self::Span;
-    fn error(&self, msg: impl fmt::Display) -> !;
+    fn error(&self, msg: impl fmt::Display) -> Self::Error;
 }
 
 impl<'a> LexerExt<'a> for Src<'a> {
     type Base = &'a str;
     type Span = Span;
+    type Error = LexerErr<'a>;
 
     fn base(&self) -> Self::Base {
         let mut base = *self;
@@ -42,18 +65,8 @@ impl<'a> LexerExt<'a> for Src<'a> {
         Span::char(self.current_token_start())
     }
 
-    fn error(&self, msg: impl fmt::Display) -> ! {
-        _ = Report::build(ariadne::ReportKind::Error, self.span())
-            .with_code(3)
-            .with_label(
-                Label::new(self.span())
-                    .with_message(msg.to_string())
-                    .with_color(ariadne::Color::BrightRed),
-            )
-            .finish()
-            .eprint(Source::from(self.base()));
-
-        std::process::exit(1);
+    fn error(&self, msg: impl fmt::Display) -> Self::Error {
+        LexerErr::new(self.base(), self.span(), msg)
     }
 }
 
@@ -69,7 +82,7 @@ impl<'a> ParserError<Src<'a>> for LexerErr<'a> {
             base: input.base(),
             span: input.span(),
             labels: Vec::new(),
-            backtrace: Backtrace::force_capture(),
+            backtrace: Backtrace::capture(),
         }
     }
 }
@@ -97,6 +110,11 @@ impl fmt::Display for LexerErr<'_> {
             .finish()
             .eprint(Source::from(self.base));
 
+        // Solo se captura con `RUST_BACKTRACE` activado
+        if self.backtrace.status() != BacktraceStatus::Captured {
+            return Ok(());
+        }
+
         let backtrace = self.backtrace.to_string();
 
         let mut backtrace = backtrace.split('\n');
@@ -105,12 +105,12 @@ impl From<bool> for Literal {
 
 impl From<String> for Literal {
     fn from(value: String) -> Self {
-        Self::String(value)
+        Self::String(value.into())
     }
 }
 
 impl From<&str> for Literal {
     fn from(value: &str) -> Self {
-        Self::String(value.to_owned())
+        Self::String(value.to_owned().into())
     }
 }
@@ -1,7 +1,12 @@
-use winnow::{Parser, combinator::preceded, token::take_while};
+use winnow::{
+    Parser,
+    combinator::{alt, preceded},
+    stream::{Location, Stream},
+    token::take_while,
+};
 
 use crate::{
-    error::{Result, Src},
+    error::{LexerExt, Result, Src},
     parsers::TokenParser,
     token::{Span, SpannedToken, Token},
 };
@@ -10,15 +15,88 @@ pub struct CommentParser;
 
 impl TokenParser for CommentParser {
     fn parse<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
-        let (content, span) = preceded("//", take_while(0.., |c: char| c != '\n' && c != '\r'))
-            .with_span()
-            .parse_next(input)?;
+        alt((line_comment, block_comment)).parse_next(input)
+    }
+}
+
+/// Comentario de línea: `// ...` hasta el fin de línea
+fn line_comment<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
+    let (content, span) = preceded("//", take_while(0.., |c: char| c != '\n' && c != '\r'))
+        .with_span()
+        .parse_next(input)?;
+
+    Ok(Some(SpannedToken::new(
+        Span::from(span),
+        Token::Comment(content.to_string()),
+    )))
+}
+
+/// Comentario de bloque: `/* ... */`, puede ocupar varias líneas y anidarse.
+///
+/// Si empieza con `/**` es un comentario de documentación, y su texto se
+/// limpia de los `*` con los que suele empezar cada línea.
+fn block_comment<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
+    if !input.starts_with("/*") {
+        return Err(input.error("Expected a comment"));
+    }
+    let Some(len) = block_comment_len(input) else {
+        return Err(input.error("Unterminated block comment"));
+    };
+
+    let start = input.current_token_start();
+    let text = input.next_slice(len);
+    let content = &text[2..len - 2];
+    let token = match content.strip_prefix('*') {
+        // `/**/` y los banners `/*****` son comentarios comunes
+        Some(doc) if !doc.is_empty() && !doc.starts_with('*') => Token::DocComment(doc_text(doc)),
+        _ => Token::Comment(content.to_string()),
+    };
 
-        Ok(Some(SpannedToken::new(
-            Span::from(span),
-            Token::Comment(content.to_string()),
-        )))
+    Ok(Some(SpannedToken::new(
+        Span::from(start..start + len),
+        token,
+    )))
+}
+
+/// Largo del comentario de bloque al comienzo de `text`, incluyendo los
+/// delimitadores, o `None` si no está cerrado
+fn block_comment_len(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut idx = 0;
+
+    while idx + 1 < bytes.len() {
+        match &bytes[idx..idx + 2] {
+            b"/*" => {
+                depth += 1;
+                idx += 2;
+            }
+            b"*/" => {
+                depth -= 1;
+                idx += 2;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => idx += 1,
+        }
     }
+
+    None
+}
+
+/// Quita el `*` inicial de cada línea de un comentario de documentación
+fn doc_text(doc: &str) -> String {
+    doc.lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*')
+                .map_or(line, |rest| rest.strip_prefix(' ').unwrap_or(rest))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
 }
 
 #[cfg(test)]
@@ -55,4 +133,51 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_block_comment() {
+        let mut input = Src::new("/* comentario\n   de bloque */ 42");
+        let token = CommentParser::parse(&mut input).unwrap().unwrap();
+
+        assert_eq!(token, cmt!(" comentario\n   de bloque "));
+        assert_eq!(*input, " 42");
+    }
+
+    #[test]
+    fn test_nested_block_comment() {
+        let mut input = Src::new("/* afuera /* adentro */ afuera */");
+        let token = CommentParser::parse(&mut input).unwrap().unwrap();
+
+        assert_eq!(token, cmt!(" afuera /* adentro */ afuera "));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_doc_comment() {
+        let mut input = Src::new("/**\n * Un ave que vuela.\n *\n * Come alpiste.\n */");
+        let token = CommentParser::parse(&mut input).unwrap().unwrap();
+
+        assert_eq!(
+            token,
+            Token::DocComment("Un ave que vuela.\n\nCome alpiste.".into())
+        );
+    }
+
+    #[test]
+    fn test_empty_and_banner_comments_are_not_docs() {
+        for source in ["/**/", "/****** banner ******/"] {
+            let mut input = Src::new(source);
+            let token = CommentParser::parse(&mut input).unwrap().unwrap();
+
+            assert!(matches!(token.token, Token::Comment(_)), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let mut input = Src::new("/* sin /* cerrar */");
+        let error = CommentParser::parse(&mut input).unwrap_err();
+
+        assert_eq!(error.message(), Some("Unterminated block comment"));
+    }
 }
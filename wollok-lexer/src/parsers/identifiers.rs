@@ -9,26 +9,14 @@ use crate::{
     error::{LexerErr, Result, Src},
     parsers::TokenParser,
     token::{Span, SpannedToken, Token},
+    utils::is_identifier_char,
 };
 
 pub struct IdentifierParser;
 
 impl TokenParser for IdentifierParser {
     fn parse<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
-        // Primero verificamos que el primer carácter no sea un dígito
-        let (first_char, _) = peek(any).with_span().parse_next(input)?;
-
-        if first_char.is_ascii_digit() {
-            return Err(LexerErr::from_input(input));
-        }
-
-        let (ident, span) = take_while(1.., |c: char| c.is_alphanumeric() || c == '_')
-            .with_span()
-            .parse_next(input)?;
-
-        if ident.is_empty() {
-            return Ok(None);
-        }
+        let (ident, span) = word.with_span().parse_next(input)?;
 
         Ok(Some(SpannedToken::new(
             Span::from(span),
@@ -37,6 +25,22 @@ impl TokenParser for IdentifierParser {
     }
 }
 
+/// Lee una palabra completa con forma de identificador. Keywords y literales
+/// como `true` o `null` la clasifican después, sin cortar identificadores más largos
+///
+/// # Errors
+/// Retorna un error si la entrada no empieza con un identificador.
+pub fn word<'t>(input: &mut Src<'t>) -> Result<'t, &'t str> {
+    // Primero verificamos que el primer carácter no sea un dígito
+    let first_char = peek(any).parse_next(input)?;
+
+    if first_char.is_ascii_digit() {
+        return Err(LexerErr::from_input(input));
+    }
+
+    take_while(1.., is_identifier_char).parse_next(input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,47 +1,70 @@
-use winnow::{Parser, combinator::alt, error::ParserError};
+use winnow::Parser;
 
 use crate::{
-    error::{LexerErr, Result, Src},
-    parsers::TokenParser,
+    error::{Result, Src},
+    parsers::{TokenParser, identifiers::word},
     token::{Keyword, Span, SpannedToken, Token},
 };
 
 pub struct KeywordParser;
 
 impl TokenParser for KeywordParser {
+    /// Lee una palabra completa y la clasifica con la tabla de keywords,
+    /// así `iffy` o `selfish` quedan como identificadores
     fn parse<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
-        let result = alt((
-            "if".value(Keyword::If),
-            "else".value(Keyword::Else),
-            "object".value(Keyword::Object),
-            "class".value(Keyword::Class),
-            "method".value(Keyword::Method),
-            "import".value(Keyword::Import),
-            "describe".value(Keyword::Describe),
-            "test".value(Keyword::Test),
-            "assert".value(Keyword::Assert),
-            "const".value(Keyword::Const),
-            "let".value(Keyword::Let),
-            "self".value(Keyword::This), // Using `self` as a keyword
-            "property".value(Keyword::Property),
-            "super".value(Keyword::Super),
-            "return".value(Keyword::Return),
-            "new".value(Keyword::New),
-            "inherits".value(Keyword::Inherits),
-            "override".value(Keyword::Override),
-            "fallible".value(Keyword::Fallible),
-        ))
-        .with_span()
-        .map(|(keyword, span)| Some(SpannedToken::new(Span::from(span), Token::Keyword(keyword))))
-        .parse_next(input)?;
-
-        match result {
-            Some(token) => Ok(Some(token)),
-            None => Err(LexerErr::from_input(input)),
-        }
+        word.verify_map(keyword)
+            .with_span()
+            .map(|(keyword, span)| {
+                Some(SpannedToken::new(Span::from(span), Token::Keyword(keyword)))
+            })
+            .parse_next(input)
     }
 }
 
+/// Tabla de keywords: devuelve la keyword si `word` es exactamente una
+#[must_use]
+pub fn keyword(word: &str) -> Option<Keyword> {
+    let keyword = match word {
+        "if" => Keyword::If,
+        "else" => Keyword::Else,
+        "object" => Keyword::Object,
+        "class" => Keyword::Class,
+        "method" => Keyword::Method,
+        "import" => Keyword::Import,
+        "describe" => Keyword::Describe,
+        "test" => Keyword::Test,
+        "assert" => Keyword::Assert,
+        "const" => Keyword::Const,
+        "let" => Keyword::Let,
+        "self" => Keyword::This, // Using `self` as a keyword
+        "property" => Keyword::Property,
+        "super" => Keyword::Super,
+        "return" => Keyword::Return,
+        "new" => Keyword::New,
+        "inherits" => Keyword::Inherits,
+        "override" => Keyword::Override,
+        "fallible" => Keyword::Fallible,
+        // Operadores con forma de palabra
+        "and" => Keyword::And,
+        "or" => Keyword::Or,
+        "not" => Keyword::Not,
+        // Excepciones
+        "throw" => Keyword::Throw,
+        "try" => Keyword::Try,
+        "catch" => Keyword::Catch,
+        // Puntos de entrada y agrupación
+        "program" => Keyword::Program,
+        "package" => Keyword::Package,
+        // Mixins
+        "mixin" => Keyword::Mixin,
+        // Métodos sin cuerpo
+        "abstract" => Keyword::Abstract,
+        "native" => Keyword::Native,
+        _ => return None,
+    };
+    Some(keyword)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,6 +100,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_word_operators() {
+        let mut input = Src::new("and");
+        let token = KeywordParser::parse(&mut input).unwrap().unwrap();
+        assert_eq!(token.token, Token::Keyword(Keyword::And));
+
+        let mut input = Src::new("or");
+        let token = KeywordParser::parse(&mut input).unwrap().unwrap();
+        assert_eq!(token.token, Token::Keyword(Keyword::Or));
+
+        let mut input = Src::new("not");
+        let token = KeywordParser::parse(&mut input).unwrap().unwrap();
+        assert_eq!(token.token, Token::Keyword(Keyword::Not));
+    }
+
+    #[test]
+    fn test_exception_keywords() {
+        let cases = [
+            ("throw", Keyword::Throw),
+            ("try", Keyword::Try),
+            ("catch", Keyword::Catch),
+        ];
+
+        for (source, expected) in cases {
+            let mut input = Src::new(source);
+            let token = KeywordParser::parse(&mut input).unwrap().unwrap();
+            assert_eq!(token.token, Token::Keyword(expected));
+        }
+
+        // `then always` solo es especial después de un try, `as` en un import
+        // y `with` en la lista de mixins, así que son nombres
+        for source in ["then", "always", "as", "with"] {
+            let mut input = Src::new(source);
+            assert!(KeywordParser::parse(&mut input).is_err());
+        }
+    }
+
+    #[test]
+    fn test_declaration_keywords() {
+        let cases = [
+            ("program", Keyword::Program),
+            ("package", Keyword::Package),
+            ("mixin", Keyword::Mixin),
+            ("abstract", Keyword::Abstract),
+            ("native", Keyword::Native),
+        ];
+
+        for (source, expected) in cases {
+            let mut input = Src::new(source);
+            let token = KeywordParser::parse(&mut input).unwrap().unwrap();
+            assert_eq!(token.token, Token::Keyword(expected));
+        }
+    }
+
+    #[test]
+    fn test_keywords_need_boundary() {
+        for source in [
+            "order",
+            "android",
+            "or_else",
+            "nothing",
+            "trying",
+            "thenable",
+            "asado",
+            "iffy",
+            "testing",
+            "newValue",
+            "letras",
+            "classroom",
+            "selfish",
+            "programa",
+            "packages",
+            "mixins",
+            "without",
+            "nativo",
+        ] {
+            let mut input = Src::new(source);
+            assert!(
+                KeywordParser::parse(&mut input).is_err(),
+                "lexing {source:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_not_a_keyword() {
         let mut input = Src::new("variable");
@@ -1,8 +1,8 @@
-use winnow::{Parser, combinator::alt};
+use winnow::Parser;
 
 use crate::{
     error::{Result, Src},
-    parsers::TokenParser,
+    parsers::{TokenParser, identifiers::word},
     token::{Literal, Span, SpannedToken, Token},
 };
 
@@ -10,15 +10,20 @@ pub struct BooleanParser;
 
 impl TokenParser for BooleanParser {
     fn parse<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
-        alt(("true".value(true), "false".value(false)))
-            .with_span()
-            .map(|(bool_val, span)| {
-                Some(SpannedToken::new(
-                    Span::from(span),
-                    Token::Literal(Literal::Boolean(bool_val)),
-                ))
-            })
-            .parse_next(input)
+        // `trueValue` es un identificador, no `true` seguido de `Value`
+        word.verify_map(|word| match word {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        })
+        .with_span()
+        .map(|(bool_val, span)| {
+            Some(SpannedToken::new(
+                Span::from(span),
+                Token::Literal(Literal::Boolean(bool_val)),
+            ))
+        })
+        .parse_next(input)
     }
 }
 
@@ -49,6 +54,17 @@ mod tests {
         assert_eq!(token, lit!(false));
     }
 
+    #[test]
+    fn test_boolean_needs_boundary() {
+        for source in ["trueValue", "falsedad", "true_"] {
+            let mut input = Src::new(source);
+            assert!(
+                BooleanParser::parse(&mut input).is_err(),
+                "lexing {source:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_not_boolean() {
         let mut input = Src::new("maybe");
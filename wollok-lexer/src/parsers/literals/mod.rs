@@ -2,7 +2,7 @@ use winnow::{Parser, combinator::alt};
 
 use crate::{
     error::{Result, Src},
-    parsers::TokenParser,
+    parsers::{TokenParser, identifiers::word},
     token::{Literal, Span, SpannedToken, Token},
 };
 
@@ -22,12 +22,14 @@ impl TokenParser for LiteralParser {
             StringParser::parse,
             NumberParser::parse,
             BooleanParser::parse,
-            "null".with_span().map(|(_null, span)| {
-                Some(SpannedToken::new(
-                    Span::from(span),
-                    Token::Literal(Literal::Null),
-                ))
-            }),
+            word.verify(|word: &str| word == "null")
+                .with_span()
+                .map(|(_null, span)| {
+                    Some(SpannedToken::new(
+                        Span::from(span),
+                        Token::Literal(Literal::Null),
+                    ))
+                }),
         ))
         .parse_next(input)
     }
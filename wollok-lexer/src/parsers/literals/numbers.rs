@@ -1,8 +1,4 @@
-use winnow::{
-    Parser,
-    combinator::opt,
-    token::{one_of, take_while},
-};
+use winnow::{Parser, combinator::opt, token::take_while};
 
 use crate::{
     error::{Result, Src},
@@ -14,9 +10,9 @@ pub struct NumberParser;
 
 impl TokenParser for NumberParser {
     fn parse<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
-        // Parseamos enteros y flotantes
+        // Parseamos enteros y flotantes. El signo no forma parte del literal:
+        // `-1` se lexea como `-` seguido de `1` para que `x-1` sea una resta
         (
-            opt(one_of(['+', '-'])), // signo opcional
             take_while(1.., |c: char| c.is_ascii_digit()).map(|s: &str| s), // parte entera
             opt((
                 '.',
@@ -24,7 +20,7 @@ impl TokenParser for NumberParser {
             )),
         )
             .with_span()
-            .map(|((sign, number, fract), span)| {
+            .map(|((number, fract), span)| {
                 let literal = if let Some(('.', fract)) = fract {
                     match format!("{number}.{fract}").parse::<f64>() {
                         Ok(f) => Some(Literal::Float(f)),
@@ -38,12 +34,6 @@ impl TokenParser for NumberParser {
                 };
 
                 let lit = literal?;
-
-                let lit = match (sign, lit) {
-                    (Some('-'), Literal::Integer(i)) => Literal::Integer(-i),
-                    (Some('-'), Literal::Float(f)) => Literal::Float(-f),
-                    (_, l) => l, // Para None o '+', mantener el valor original
-                };
                 Some(SpannedToken::new(Span::from(span), Token::Literal(lit)))
             })
             .parse_next(input)
@@ -67,14 +57,11 @@ mod tests {
     }
 
     #[test]
-    fn test_negative_integer() {
+    fn test_sign_is_not_part_of_integer() {
         let mut input = Src::new("-15");
-        let result = NumberParser::parse(&mut input).unwrap();
+        let result = NumberParser::parse(&mut input);
 
-        assert!(result.is_some());
-        let token = result.unwrap();
-
-        assert_eq!(token, lit!(-15));
+        assert!(result.is_err());
     }
 
     #[test]
@@ -90,13 +77,10 @@ mod tests {
     }
 
     #[test]
-    fn test_negative_float() {
-        let mut input = Src::new("-2.5");
-        let result = NumberParser::parse(&mut input).unwrap();
-
-        assert!(result.is_some());
-        let token = result.unwrap();
+    fn test_sign_is_not_part_of_float() {
+        let mut input = Src::new("+2.5");
+        let result = NumberParser::parse(&mut input);
 
-        assert_eq!(token, lit!(-2.5));
+        assert!(result.is_err());
     }
 }
@@ -1,40 +1,135 @@
-use winnow::{
-    Parser,
-    combinator::{alt, delimited},
-    token::take_while,
-};
+use std::ops::Range;
+
+use winnow::stream::{Location, Stream};
 
 use crate::{
-    error::{Result, Src},
+    error::{LexerErr, LexerExt, Result, Src},
     parsers::TokenParser,
-    token::{Literal, Span, SpannedToken, Token},
+    token::{Literal, Span, SpannedToken, StringLit, Token},
 };
 
 pub struct StringParser;
 
 impl TokenParser for StringParser {
+    /// Parsea una string con comillas simples o dobles, que puede ocupar
+    /// varias líneas, decodificando sus secuencias de escape.
+    ///
+    /// Si tiene un escape inválido la string se consume entera y el error
+    /// apunta solo al escape.
     fn parse<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
-        alt((
-            // String con comillas dobles
-            delimited('"', take_while(0.., |c: char| c != '"' && c != '\n'), '"').map(|s: &str| s),
-            // String con comillas simples
-            delimited(
-                '\'',
-                take_while(0.., |c: char| c != '\'' && c != '\n'),
-                '\'',
-            )
-            .map(|s: &str| s),
-        ))
-        .with_span()
-        .map(|(content, span)| {
-            let string_content = content.to_string();
-            Some(SpannedToken::new(
-                Span::from(span),
-                Token::Literal(Literal::String(string_content)),
-            ))
-        })
-        .parse_next(input)
+        let start = input.current_token_start();
+        let Some(quote @ ('"' | '\'')) = input.chars().next() else {
+            return Err(input.error("Expected a string literal"));
+        };
+        let Some((len, content, invalid)) = scan(input, quote) else {
+            return Err(input.error("Unterminated string literal"));
+        };
+
+        let raw = input.next_slice(len);
+        match invalid {
+            None => Ok(Some(SpannedToken::new(
+                Span::from(start..start + len),
+                Token::Literal(Literal::String(StringLit::new(content, raw.to_owned()))),
+            ))),
+            Some(escape) => Err(LexerErr::new(
+                input.base(),
+                Span::from(start + escape.start..start + escape.end),
+                format!("Invalid escape sequence `{}`", &raw[escape]),
+            )),
+        }
+    }
+}
+
+impl StringParser {
+    /// Decodifica una string completa que tiene escapes inválidos, dejándolos
+    /// tal como están escritos. Sirve para no perder el literal al recuperarse
+    /// del error.
+    #[must_use]
+    pub fn lossy(raw: &str) -> Token {
+        let content = raw
+            .chars()
+            .next()
+            .and_then(|quote| scan(raw, quote))
+            .map_or_else(String::new, |(_, content, _)| content);
+        Token::Literal(Literal::String(StringLit::new(content, raw.to_owned())))
+    }
+}
+
+/// Recorre la string hasta la comilla de cierre.
+///
+/// Devuelve el largo de la string con sus comillas, el contenido decodificado
+/// (con los escapes inválidos tal como están escritos) y el rango del primer
+/// escape inválido. `None` si la string no se cierra.
+fn scan(text: &str, quote: char) -> Option<(usize, String, Option<Range<usize>>)> {
+    let mut content = String::new();
+    let mut invalid = None;
+    let mut idx = quote.len_utf8();
+
+    while let Some(c) = text[idx..].chars().next() {
+        let next = idx + c.len_utf8();
+        if c == quote {
+            return Some((next, content, invalid));
+        }
+        if c != '\\' {
+            content.push(c);
+            idx = next;
+            continue;
+        }
+
+        let (escaped, len) = escape(&text[next..]);
+        if len == 0 {
+            // Una `\` al final del input
+            return None;
+        }
+        if let Some(escaped) = escaped {
+            content.push(escaped);
+        } else {
+            content.push_str(&text[idx..next + len]);
+            invalid.get_or_insert(idx..next + len);
+        }
+        idx = next + len;
     }
+
+    None
+}
+
+/// Decodifica el escape que sigue a una `\`, devolviendo también cuántos
+/// bytes ocupa
+fn escape(rest: &str) -> (Option<char>, usize) {
+    let Some(c) = rest.chars().next() else {
+        return (None, 0);
+    };
+    let escaped = match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        '0' => '\0',
+        '\\' | '"' | '\'' => c,
+        'u' => return unicode_escape(&rest[1..]),
+        _ => return (None, c.len_utf8()),
+    };
+    (Some(escaped), 1)
+}
+
+/// Decodifica un escape unicode como `\u{f1}`, sin la `\u` inicial
+fn unicode_escape(rest: &str) -> (Option<char>, usize) {
+    let Some(body) = rest.strip_prefix('{') else {
+        return (None, 1);
+    };
+    let digits = body
+        .find(|c: char| !c.is_ascii_hexdigit())
+        .map_or(body, |end| &body[..end]);
+    if !body[digits.len()..].starts_with('}') {
+        return (None, 2 + digits.len());
+    }
+
+    let escaped = u32::from_str_radix(digits, 16)
+        .ok()
+        .filter(|_| digits.len() <= 6)
+        .and_then(char::from_u32);
+    (escaped, 3 + digits.len())
 }
 
 #[cfg(test)]
@@ -82,4 +177,64 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_escape_sequences() {
+        let cases = [
+            (r#""dijo \"hola\"""#, "dijo \"hola\""),
+            (r"'it\'s'", "it's"),
+            (r#""a\nb\tc\rd""#, "a\nb\tc\rd"),
+            (r#""barra \\ y \0""#, "barra \\ y \0"),
+            (r#""ma\u{f1}ana""#, "mañana"),
+            (r#""\u{1F980}""#, "🦀"),
+            ("\"ñandú\"", "ñandú"),
+        ];
+
+        for (source, expected) in cases {
+            let mut input = Src::new(source);
+            let token = StringParser::parse(&mut input).unwrap().unwrap();
+
+            assert_eq!(token, lit!(expected), "{source}");
+            assert!(input.is_empty(), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_multiline_string() {
+        let mut input = Src::new("\"primera\nsegunda\"");
+        let token = StringParser::parse(&mut input).unwrap().unwrap();
+
+        assert_eq!(token, lit!("primera\nsegunda"));
+    }
+
+    #[test]
+    fn test_escaped_quote_does_not_close() {
+        let mut input = Src::new(r#""sin cerrar\""#);
+        let error = StringParser::parse(&mut input).unwrap_err();
+
+        assert_eq!(error.message(), Some("Unterminated string literal"));
+    }
+
+    #[test]
+    fn test_invalid_escapes() {
+        let cases = [
+            (r#""hola \q""#, 6..8, r"\q"),
+            (r#""\u{zz}""#, 1..4, r"\u{"),
+            (r#""\u{110000}""#, 1..11, r"\u{110000}"),
+            (r#""ñ \é""#, 4..7, r"\é"),
+        ];
+
+        for (source, range, escape) in cases {
+            let mut input = Src::new(source);
+            let error = StringParser::parse(&mut input).unwrap_err();
+
+            assert_eq!((error.span.from, error.span.to), (range.start, range.end));
+            assert_eq!(
+                error.message(),
+                Some(format!("Invalid escape sequence `{escape}`").as_str())
+            );
+            // La string se consume entera aunque tenga errores
+            assert!(input.is_empty(), "{source}");
+        }
+    }
 }
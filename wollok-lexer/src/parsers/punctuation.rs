@@ -11,23 +11,35 @@ pub struct PunctuationParser;
 impl TokenParser for PunctuationParser {
     fn parse<'t>(input: &mut Src<'t>) -> Result<'t, Option<SpannedToken>> {
         alt([
-            // Operadores de dos caracteres primero
+            // Operadores de tres caracteres primero
+            "===".value(Punctuation::StrictEq),
+            "!==".value(Punctuation::StrictNe),
+            // Operadores de dos caracteres
             "->".value(Punctuation::Arrow),
+            "=>".value(Punctuation::FatArrow),
             "==".value(Punctuation::Eq),
             "!=".value(Punctuation::Ne),
+            "<=".value(Punctuation::Le),
+            ">=".value(Punctuation::Ge),
             "&&".value(Punctuation::And),
             "||".value(Punctuation::Or),
+            "**".value(Punctuation::Pow),
             // Operadores aritméticos
             "+".value(Punctuation::Plus),
             "-".value(Punctuation::Minus),
             "*".value(Punctuation::Multiply),
             "/".value(Punctuation::Div),
+            "%".value(Punctuation::Modulo),
+            // Operadores de comparación
+            "<".value(Punctuation::Lt),
+            ">".value(Punctuation::Gt),
             // // Operadores bitwise
             "|".value(Punctuation::BitOr),
             "&".value(Punctuation::BitAnd),
             // Operadores de un caracter
             "=".value(Punctuation::Equals),
             "!".value(Punctuation::Bang),
+            "?".value(Punctuation::Question),
             ",".value(Punctuation::Comma),
             ";".value(Punctuation::Semicolon),
             ":".value(Punctuation::Colon),
@@ -93,4 +105,29 @@ mod tests {
 
         assert_eq!(token.token, T!(OpenBrace));
     }
+
+    #[test]
+    fn test_longest_operator_wins() {
+        let cases = [
+            ("===", T!(StrictEq)),
+            ("!==", T!(StrictNe)),
+            ("<=", T!(Le)),
+            (">=", T!(Ge)),
+            ("<", T!(Lt)),
+            (">", T!(Gt)),
+            ("**", T!(Pow)),
+            ("=>", T!(FatArrow)),
+            ("->", T!(Arrow)),
+            ("*", T!(Multiply)),
+            ("%", T!(Modulo)),
+        ];
+
+        for (source, expected) in cases {
+            let mut input = Src::new(source);
+            let token = PunctuationParser::parse(&mut input).unwrap().unwrap();
+
+            assert_eq!(token.token, expected, "lexing {source:?}");
+            assert!(input.is_empty(), "leftover input lexing {source:?}");
+        }
+    }
 }
@@ -3,19 +3,27 @@ use std::collections::VecDeque;
 use tracing::{debug, info, trace, warn};
 
 use crate::{
-    error::{Result, Src},
+    error::{LexerErr, LexerExt, Result, Src},
     parsers::{
         CommentParser, IdentifierParser, KeywordParser, LiteralParser, OperatorParser,
-        PunctuationParser, TokenParser, WhitespaceParser,
+        PunctuationParser, TokenParser, WhitespaceParser, literals::StringParser,
     },
-    token::SpannedToken,
+    token::{Span, SpannedToken, Token},
+    utils::is_token_start,
 };
 
-use winnow::{Parser, combinator::alt, error::ParserError};
+use winnow::{
+    Parser,
+    combinator::alt,
+    error::ParserError,
+    stream::{Location, Stream},
+};
 
 pub struct TokenStream<'t> {
     input: Src<'t>,
     finished: bool,
+    recover: bool,
+    errors: Vec<LexerErr<'t>>,
 }
 
 impl<'t> TokenStream<'t> {
@@ -32,9 +40,28 @@ impl<'t> TokenStream<'t> {
         Self {
             input: Src::new(input),
             finished: false,
+            recover: false,
+            errors: Vec::new(),
         }
     }
 
+    /// Activa el modo tolerante a errores.
+    ///
+    /// En lugar de cortar en el primer error, el stream emite un
+    /// `Token::Error` con el span del input inválido, se resincroniza en el
+    /// siguiente límite de token plausible y acumula el diagnóstico.
+    #[must_use]
+    pub fn recovering(mut self) -> Self {
+        self.recover = true;
+        self
+    }
+
+    /// Errores léxicos acumulados hasta el momento (solo en modo tolerante)
+    #[must_use]
+    pub fn errors(&self) -> &[LexerErr<'t>] {
+        &self.errors
+    }
+
     /// Intenta parsear el siguiente token
     fn next_token(&mut self) -> Result<'t, Option<SpannedToken>> {
         if self.finished {
@@ -58,21 +85,45 @@ impl<'t> TokenStream<'t> {
             return self.next_token();
         }
 
-        let result = alt((
-            CommentParser::parse,     // Comentarios
-            KeywordParser::parse,     // Keywords antes que identifiers
-            LiteralParser::parse,     // Literales (números, strings, booleans)
-            IdentifierParser::parse,  // Identificadores
-            PunctuationParser::parse, // Puntuación
-            OperatorParser::parse,    // Operadores
-        ))
-        .parse_next(&mut self.input);
+        let start = self.input.current_token_start();
+
+        // Un `/*` sin cerrar es un error, no una división seguida de `*`
+        let result = if self.input.starts_with("/*") {
+            CommentParser::parse(&mut self.input)
+        } else if self.input.starts_with(['"', '\'']) {
+            // Así se conserva el error de la string en lugar del de otro parser
+            StringParser::parse(&mut self.input)
+        } else {
+            alt((
+                CommentParser::parse,     // Comentarios
+                KeywordParser::parse,     // Keywords antes que identifiers
+                LiteralParser::parse,     // Literales (números, strings, booleans)
+                IdentifierParser::parse,  // Identificadores
+                PunctuationParser::parse, // Puntuación
+                OperatorParser::parse,    // Operadores
+            ))
+            .parse_next(&mut self.input)
+        };
 
         match result {
             Ok(Some(token)) => {
                 debug!("Successfully parsed token: {:?}", token);
                 Ok(Some(token))
             }
+            // La string se consumió entera, solo tenía escapes inválidos:
+            // se conserva el literal con esos escapes sin decodificar
+            Err(e) if self.recover && self.input.current_token_start() > start => {
+                let span = Span::from(start..self.input.current_token_start());
+                warn!(
+                    "Recovering from lexer error at {:?}: {:?}",
+                    span,
+                    e.message()
+                );
+                self.errors.push(e);
+                let raw = &self.input.base()[span.from..span.to];
+                Ok(Some(SpannedToken::new(span, StringParser::lossy(raw))))
+            }
+            Ok(None) | Err(_) if self.recover => Ok(Some(self.resync())),
             Ok(None) => {
                 // Ningún parser pudo manejar el input, esto es un error
                 warn!(
@@ -80,7 +131,7 @@ impl<'t> TokenStream<'t> {
                     &self.input.to_string().chars().take(10).collect::<String>()
                 );
                 self.finished = true;
-                Err(crate::error::LexerErr::from_input(&self.input))
+                Err(LexerErr::from_input(&self.input))
             }
             Err(e) => {
                 warn!("Parser error encountered: {:?}", e);
@@ -90,6 +141,58 @@ impl<'t> TokenStream<'t> {
         }
     }
 
+    /// Saltea el input inválido hasta el siguiente límite de token plausible,
+    /// registra el error y devuelve el `Token::Error` correspondiente
+    fn resync(&mut self) -> SpannedToken {
+        let start = self.input.current_token_start();
+        let rest = *self.input;
+
+        let mut chars = rest.char_indices();
+        let first = chars.next().map_or('\0', |(_, c)| c);
+
+        let (len, msg) = if matches!(first, '"' | '\'') {
+            // String sin cerrar: como puede ocupar varias líneas, se come el resto del input
+            (rest.len(), String::from("Unterminated string literal"))
+        } else if rest.starts_with("/*") {
+            // Comentario de bloque sin cerrar: se come el resto del input
+            (rest.len(), String::from("Unterminated block comment"))
+        } else {
+            let len = chars
+                .find(|&(_, c)| is_token_start(c))
+                .map_or(rest.len(), |(idx, _)| idx);
+            (len, format!("Unexpected input `{}`", &rest[..len]))
+        };
+
+        _ = self.input.next_slice(len);
+        let span = Span::from(start..start + len);
+        warn!("Recovering from lexer error at {:?}: {}", span, msg);
+
+        self.errors
+            .push(LexerErr::new(self.input.base(), span, &msg));
+        SpannedToken::new(span, Token::Error(msg))
+    }
+
+    /// Recolecta todos los tokens restantes en modo tolerante, devolviendo
+    /// también todos los errores léxicos encontrados.
+    ///
+    /// Los tokens inválidos quedan en el resultado como `Token::Error`.
+    #[must_use]
+    pub fn collect_recovering(self) -> (Vec<SpannedToken>, Vec<LexerErr<'t>>) {
+        let mut stream = self.recovering();
+        let mut tokens = Vec::new();
+
+        while let Ok(Some(token)) = stream.next() {
+            tokens.push(token);
+        }
+
+        info!(
+            "Collected {} tokens with {} lexer errors",
+            tokens.len(),
+            stream.errors.len()
+        );
+        (tokens, stream.errors)
+    }
+
     /// Recolecta todos los tokens restantes en un Vec
     ///
     /// # Errors
@@ -147,7 +250,7 @@ impl<'t> TokenStream<'t> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{T, ident, kw, lit};
+    use crate::{T, cmt, ident, kw, lit, token::Literal};
 
     use super::*;
 
@@ -313,6 +416,142 @@ class Deposito {
         assert_eq!(token.token, lit!(true));
     }
 
+    #[test]
+    fn test_strict_mode_stops_on_error() {
+        let stream = TokenStream::new("const a = @ 1");
+        assert!(stream.collect_all().is_err());
+    }
+
+    #[test]
+    fn test_recovering_emits_error_token() {
+        let stream = TokenStream::new("const a = @ 1");
+        let (tokens, errors) = stream.collect_recovering();
+
+        assert_eq!(
+            tokens,
+            vec![
+                kw!(Const),
+                ident!("a"),
+                T!(Equals),
+                Token::Error("Unexpected input `@`".into()),
+                lit!(1)
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span.from, 10);
+        assert_eq!(errors[0].span.to, 11);
+    }
+
+    #[test]
+    fn test_recovering_collects_all_errors() {
+        let source = "object o {\n  const a = @@ 1\n  const b = ~2\n}";
+        let (tokens, errors) = TokenStream::new(source).collect_recovering();
+
+        let messages = errors
+            .iter()
+            .filter_map(|e| e.message())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec!["Unexpected input `@@`", "Unexpected input `~`"]
+        );
+        assert_eq!(tokens.last().unwrap().token, T!(CloseBrace));
+        assert!(tokens.iter().any(|t| *t == lit!(2)));
+    }
+
+    #[test]
+    fn test_recovering_unterminated_string() {
+        let source = "const a = \"sin cerrar\nconst b = 2";
+        let (tokens, errors) = TokenStream::new(source).collect_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), Some("Unterminated string literal"));
+        // Los strings pueden ocupar varias líneas, así que el error llega al final
+        assert_eq!(
+            tokens[3..],
+            vec![Token::Error("Unterminated string literal".into())]
+        );
+        assert_eq!((errors[0].span.from, errors[0].span.to), (10, source.len()));
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let source = "const a /* uno\n dos */ = 1 /** doc */\nmethod";
+        let tokens = TokenStream::new(source).collect_all().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                kw!(Const),
+                ident!("a"),
+                cmt!(" uno\n dos "),
+                T!(Equals),
+                lit!(1),
+                Token::DocComment("doc".into()),
+                T!(Newline),
+                kw!(Method),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let source = "const a = 1 /* sin cerrar\nconst b = 2";
+        assert!(TokenStream::new(source).collect_all().is_err());
+
+        let (tokens, errors) = TokenStream::new(source).collect_recovering();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), Some("Unterminated block comment"));
+        assert_eq!(
+            tokens[3..],
+            vec![lit!(1), Token::Error("Unterminated block comment".into())]
+        );
+    }
+
+    #[test]
+    fn test_multiline_strings_and_raw_text() {
+        let source = "const a = \"uno\\n\ndos\"\nconst b = 'c'";
+        let tokens = TokenStream::new(source).collect_all().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                kw!(Const),
+                ident!("a"),
+                T!(Equals),
+                lit!("uno\n\ndos"),
+                T!(Newline),
+                kw!(Const),
+                ident!("b"),
+                T!(Equals),
+                lit!("c"),
+            ]
+        );
+        assert_eq!(tokens[3].raw(source), "\"uno\\n\ndos\"");
+        assert_eq!(tokens[8].raw(source), "'c'");
+
+        // El literal también guarda su texto original
+        let Token::Literal(Literal::String(string)) = &tokens[8].token else {
+            panic!("Expected a string literal");
+        };
+        assert_eq!(string.raw, "'c'");
+        // Las strings armadas a mano se escriben con sus escapes
+        assert_eq!(lit!("dijo \"hola\"\n").to_string(), r#""dijo \"hola\"\n""#);
+    }
+
+    #[test]
+    fn test_recovering_invalid_escape() {
+        let source = "const a = \"mal \\q\" + 1";
+        let error = TokenStream::new(source).collect_all().unwrap_err();
+        assert_eq!(error.message(), Some("Invalid escape sequence `\\q`"));
+        assert_eq!((error.span.from, error.span.to), (15, 17));
+
+        // Al recuperarse se conserva la string, con el escape tal como está escrito
+        let (tokens, errors) = TokenStream::new(source).collect_recovering();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(tokens[3..], vec![lit!("mal \\q"), T!(Plus), lit!(1)]);
+    }
+
     #[test]
     fn test_collect_all() {
         let stream = TokenStream::new("42 + 3");
@@ -320,4 +559,45 @@ class Deposito {
 
         assert_eq!(tokens, vec![lit!(42), T!(Plus), lit!(3)]);
     }
+
+    #[test]
+    fn test_minus_is_never_part_of_number() {
+        let tokens = TokenStream::new("x-1 a -1").collect_all().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![ident!(x), T!(Minus), lit!(1), ident!(a), T!(Minus), lit!(1)]
+        );
+    }
+
+    #[test]
+    fn test_identifiers_starting_with_keywords() {
+        let source = "iffy testing newValue letras classroom selfish nullable trueValue \
+                      falsedad constante retorno1 objeto superior importe notas android";
+        let tokens = TokenStream::new(source).collect_all().unwrap();
+
+        let expected = source
+            .split_whitespace()
+            .map(|name| SpannedToken::new(Span::ZERO, Token::Ident(name.to_owned())))
+            .collect::<Vec<_>>();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn test_keywords_next_to_punctuation() {
+        let tokens = TokenStream::new("if(true)self.x").collect_all().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                kw!(If),
+                T!(OpenParen),
+                lit!(true),
+                T!(CloseParen),
+                kw!(This),
+                T!(Dot),
+                ident!(x),
+            ]
+        );
+    }
 }
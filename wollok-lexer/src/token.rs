@@ -70,6 +70,13 @@ impl SpannedToken {
     pub fn split(&self) -> (Span, Token) {
         (self.span, self.token.clone())
     }
+
+    /// Texto del token tal como aparece en `source`, por ejemplo una string
+    /// con sus comillas y escapes sin decodificar
+    #[must_use]
+    pub fn raw<'s>(&self, source: &'s str) -> &'s str {
+        &source[self.span.from..self.span.to]
+    }
 }
 
 impl std::ops::Deref for SpannedToken {
@@ -95,10 +102,12 @@ impl PartialEq<Token> for SpannedToken {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Comment(String),          // comments
+    DocComment(String),       // `/** ... */` documentation
     Ident(String),            // key
     Punctuation(Punctuation), // punctuation
     Literal(Literal),         // values
     Keyword(Keyword),         // for meta-programming
+    Error(String),            // invalid input, only emitted when recovering
 }
 
 impl Token {
@@ -110,16 +119,29 @@ impl Token {
             None
         }
     }
+
+    /// Nombre de un mensaje: después de un `.` las keywords también son nombres
+    /// válidos, como en `a.and(b)` o `y.not()`
+    #[must_use]
+    pub fn into_selector(&self) -> Option<String> {
+        match self {
+            Self::Ident(ident) => Some(ident.clone()),
+            Self::Keyword(keyword) => Some(keyword.to_string()),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Token::Comment(comment) => write!(f, "# {comment}"),
+            Token::DocComment(doc) => write!(f, "/** {doc} */"),
             Token::Ident(ident) => write!(f, "{ident}"),
             Token::Punctuation(punct) => write!(f, "{punct}"),
             Token::Literal(lit) => write!(f, "{lit}"),
             Token::Keyword(keyword) => write!(f, "{keyword}"),
+            Token::Error(msg) => write!(f, "<error: {msg}>"),
         }
     }
 }
@@ -138,6 +160,8 @@ pub enum Punctuation {
     Minus,
     Multiply,
     Div,
+    Modulo, // %
+    Pow,    // **
 
     // Bitwise
     BitAnd,
@@ -147,12 +171,20 @@ pub enum Punctuation {
     Equals,
     // ==
     Eq,
-    Ne,   // !=
-    And,  // &&
-    Or,   // ||
-    Bang, // !, used for negation
-
-    Arrow,
+    Ne,       // !=
+    StrictEq, // ===
+    StrictNe, // !==
+    Lt,       // <
+    Gt,       // >
+    Le,       // <=
+    Ge,       // >=
+    And,      // &&
+    Or,       // ||
+    Bang,     // !, used for negation and assertion
+    Question, // ?, used for error propagation
+
+    Arrow,    // ->
+    FatArrow, // =>
     Newline,
     Identation,
     OpenBrace,
@@ -177,6 +209,8 @@ impl fmt::Display for Punctuation {
             Punctuation::Minus => "-",
             Punctuation::Multiply => "*",
             Punctuation::Div => "/",
+            Punctuation::Modulo => "%",
+            Punctuation::Pow => "**",
 
             Punctuation::BitAnd => "&",
             Punctuation::BitOr => "|",
@@ -184,11 +218,19 @@ impl fmt::Display for Punctuation {
             Punctuation::Equals => "=",
             Punctuation::Eq => "==",
             Punctuation::Ne => "!=",
+            Punctuation::StrictEq => "===",
+            Punctuation::StrictNe => "!==",
+            Punctuation::Lt => "<",
+            Punctuation::Gt => ">",
+            Punctuation::Le => "<=",
+            Punctuation::Ge => ">=",
             Punctuation::And => "&&",
             Punctuation::Or => "||",
             Punctuation::Bang => "!",
+            Punctuation::Question => "?",
 
             Punctuation::Arrow => "->",
+            Punctuation::FatArrow => "=>",
             Punctuation::Newline => "\n",
             Punctuation::Identation => "\t",
             Punctuation::OpenBrace => "{",
@@ -204,17 +246,17 @@ impl fmt::Display for Punctuation {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
-    String(String), // "hello world" | 'hello world'
-    Integer(i64),   // 42
-    Float(f64),     // 3.14
-    Boolean(bool),  // true or false
-    Null,           // null
+    String(StringLit), // "hello world" | 'hello world'
+    Integer(i64),      // 42
+    Float(f64),        // 3.14
+    Boolean(bool),     // true or false
+    Null,              // null
 }
 
 impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Literal::String(s) => write!(f, "\"{s}\""),
+            Literal::String(s) => write!(f, "{s}"),
             Literal::Integer(i) => write!(f, "{i}"),
             Literal::Float(fl) => write!(f, "{fl}"),
             Literal::Boolean(b) => write!(f, "{b}"),
@@ -223,6 +265,57 @@ impl fmt::Display for Literal {
     }
 }
 
+/// Una string con sus escapes ya decodificados, junto con el texto tal como
+/// aparece en el código (comillas y escapes incluidos) para los formateadores.
+///
+/// Como con `SpannedToken`, la igualdad solo compara el contenido decodificado.
+#[derive(Debug, Clone)]
+pub struct StringLit {
+    pub value: String,
+    pub raw: String,
+}
+
+impl StringLit {
+    #[must_use]
+    pub fn new(value: String, raw: String) -> Self {
+        Self { value, raw }
+    }
+}
+
+/// Arma el texto de una string escrita a mano, escapando lo necesario
+impl From<String> for StringLit {
+    fn from(value: String) -> Self {
+        let mut raw = String::from('"');
+        for c in value.chars() {
+            match c {
+                '"' => raw.push_str("\\\""),
+                '\\' => raw.push_str("\\\\"),
+                '\n' => raw.push_str("\\n"),
+                '\t' => raw.push_str("\\t"),
+                '\r' => raw.push_str("\\r"),
+                '\u{8}' => raw.push_str("\\b"),
+                '\u{c}' => raw.push_str("\\f"),
+                '\0' => raw.push_str("\\0"),
+                c => raw.push(c),
+            }
+        }
+        raw.push('"');
+        Self { value, raw }
+    }
+}
+
+impl PartialEq for StringLit {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl fmt::Display for StringLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Keyword {
     Const,
@@ -245,6 +338,16 @@ pub enum Keyword {
     Mixin,
     Inherits,
     Fallible,
+    And, // `and`, same as `&&`
+    Or,  // `or`, same as `||`
+    Not, // `not`, same as `!`
+    Throw,
+    Try,
+    Catch,
+    Program,
+    Package,
+    Abstract,
+    Native,
 }
 
 impl fmt::Display for Keyword {
@@ -270,6 +373,16 @@ impl fmt::Display for Keyword {
             Keyword::Mixin => "mixin",
             Keyword::Inherits => "inherits",
             Keyword::Fallible => "fallible",
+            Keyword::And => "and",
+            Keyword::Or => "or",
+            Keyword::Not => "not",
+            Keyword::Throw => "throw",
+            Keyword::Try => "try",
+            Keyword::Catch => "catch",
+            Keyword::Program => "program",
+            Keyword::Package => "package",
+            Keyword::Abstract => "abstract",
+            Keyword::Native => "native",
         };
         write!(f, "{keyword}")
     }
@@ -26,8 +26,5 @@ where
 /// Helper para verificar que un identificador no sea una keyword
 #[must_use]
 pub fn not_keyword(ident: &str) -> bool {
-    !matches!(
-        ident,
-        "if" | "else" | "object" | "class" | "method" | "import" | "describe" | "assert"
-    )
+    crate::parsers::keywords::keyword(ident).is_none()
 }
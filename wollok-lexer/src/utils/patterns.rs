@@ -39,11 +39,23 @@ pub fn is_operator_char(c: char) -> bool {
     )
 }
 
+#[must_use]
+/// Verifica si un caracter puede ser el comienzo de algún token válido
+pub fn is_token_start(c: char) -> bool {
+    is_identifier_start(c)
+        || is_numeric(c)
+        || is_whitespace(c)
+        || is_newline(c)
+        || is_operator_char(c)
+        || is_punctuation(c)
+        || matches!(c, '"' | '\'' | '#')
+}
+
 #[must_use]
 /// Verifica si un caracter es puntuación
 pub fn is_punctuation(c: char) -> bool {
     matches!(
         c,
-        ',' | ';' | ':' | '.' | '$' | '(' | ')' | '{' | '}' | '[' | ']'
+        ',' | ';' | ':' | '.' | '$' | '?' | '(' | ')' | '{' | '}' | '[' | ']'
     )
 }